@@ -0,0 +1,613 @@
+use std::time::Duration;
+
+use druid::im::OrdSet;
+use druid::widget::{
+    Button, Container, CrossAxisAlignment, Either, Flex, Label, MainAxisAlignment, RadioGroup,
+    SizedBox, TextBox, ViewSwitcher, ZStack,
+};
+use druid::{
+    AppLauncher, Color, Data, Env, EventCtx, Lens, PlatformError, Point, Size, UnitPoint, Widget,
+    WidgetExt, WindowDesc,
+};
+use druid_gio_widgets::prelude::*;
+use druid_gio_widgets::theme::{self, Preset, ThemeScope};
+
+const SIDEBAR_WIDTH: f64 = 180.0;
+const PANEL_WIDTH: f64 = 220.0;
+
+selectors! {
+    /// Submitted by the retry action of the gallery's error toast
+    RETRY,
+}
+
+#[derive(Debug, Clone, Copy, Data, PartialEq)]
+enum Page {
+    Button,
+    Dropdown,
+    Select,
+    Pickers,
+    Sliders,
+    Tabs,
+    Table,
+    Tree,
+    List,
+    Popup,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+struct ButtonProps {
+    label: String,
+    disabled: bool,
+    surface: Color,
+    accent: Color,
+    /// The sections of the properties panel that are open
+    sections: OrdSet<usize>,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+struct PlaceProps {
+    place: String,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+struct PickerProps {
+    date: Date,
+    meeting: DateTime,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+struct SliderProps {
+    volume: f64,
+    price: (f64, f64),
+}
+
+#[derive(Debug, Clone, Data)]
+struct FileRow {
+    name: String,
+    kind: &'static str,
+    size: u64,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+struct GalleryState {
+    page: Page,
+    theme: Preset,
+    button: ButtonProps,
+    dropdown: PlaceProps,
+    select: PlaceProps,
+    pickers: PickerProps,
+    sliders: SliderProps,
+    tabs: TabsState<String>,
+    files: TableState<FileRow>,
+    scene: TreeState<String>,
+    log: VirtualListState<String>,
+    show_popup: bool,
+}
+
+fn sidebar() -> impl Widget<GalleryState> {
+    Flex::column()
+        .with_child(Label::new("Widgets").with_text_size(18.0))
+        .with_default_spacer()
+        .with_child(
+            RadioGroup::column(vec![
+                ("AdvancedButton", Page::Button),
+                ("Dropdown", Page::Dropdown),
+                ("Select", Page::Select),
+                ("Pickers", Page::Pickers),
+                ("Sliders", Page::Sliders),
+                ("Tabs", Page::Tabs),
+                ("Table", Page::Table),
+                ("Tree", Page::Tree),
+                ("List", Page::List),
+                ("Popup", Page::Popup),
+            ])
+            .lens(GalleryState::page),
+        )
+        .with_flex_spacer(1.0)
+        .with_child(Label::new("Theme"))
+        .with_child(
+            RadioGroup::row(vec![("Light", Preset::Light), ("Dark", Preset::Dark)])
+                .lens(GalleryState::theme),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .padding(12.0)
+        .fix_width(SIDEBAR_WIDTH)
+        .expand_height()
+}
+
+// A page lays out the widget's variants next to the panel tweaking them.
+fn page<T: Data>(
+    title: &str,
+    variants: impl Widget<T> + 'static,
+    panel: impl Widget<T> + 'static,
+) -> Box<dyn Widget<T>> {
+    let variants = Flex::column()
+        .with_child(Label::new(title).with_text_size(24.0))
+        .with_spacer(16.0)
+        .with_child(variants)
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .padding(20.0)
+        .expand();
+    let panel = Flex::column()
+        .with_child(Label::new("Properties").with_text_size(16.0))
+        .with_default_spacer()
+        .with_child(panel)
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .padding(12.0)
+        .fix_width(PANEL_WIDTH)
+        .expand_height()
+        .border(theme::BORDER, 1.0);
+
+    Flex::row()
+        .with_flex_child(variants, 1.0)
+        .with_child(panel)
+        .boxed()
+}
+
+fn variant<T: Data>(name: &str, widget: impl Widget<T> + 'static) -> impl Widget<T> {
+    Flex::row()
+        .with_child(Label::new(name).fix_width(120.0))
+        .with_child(widget)
+        .padding((0.0, 6.0))
+}
+
+fn button_page() -> Box<dyn Widget<GalleryState>> {
+    let live = || {
+        AdvancedButton::new(|props: &ButtonProps, _: &Env| props.label.clone())
+            .disabled_if(|props: &ButtonProps, _| props.disabled)
+    };
+    let variants = Flex::column()
+        .with_child(variant("Normal", live()))
+        .with_child(variant("Disabled", live().disabled_if(|_, _| true)))
+        .with_child(variant(
+            "Long label",
+            AdvancedButton::new(|props: &ButtonProps, _: &Env| {
+                format!("{} with a much longer label", props.label)
+            }),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .env_scope(|env, props: &ButtonProps| {
+            env.set(theme::SURFACE, props.surface);
+            env.set(theme::ACCENT, props.accent);
+        });
+
+    let content = Flex::column()
+        .with_child(Label::new("Label"))
+        .with_child(TextBox::new().lens(ButtonProps::label).expand_width())
+        .with_default_spacer()
+        .with_child(
+            Toggle::new()
+                .with_label("Disabled")
+                .lens(ButtonProps::disabled),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+    let colors = Flex::column()
+        .with_child(variant(
+            "Surface",
+            ColorPicker::new().lens(ButtonProps::surface),
+        ))
+        .with_child(variant(
+            "Accent",
+            ColorPicker::new().lens(ButtonProps::accent),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+    let panel = Accordion::multiple(ButtonProps::sections)
+        .with_section("Content", content.padding((0.0, 0.0, 0.0, 8.0)))
+        .with_section("Colors", colors);
+
+    page("AdvancedButton", variants, panel)
+        .lens(GalleryState::button)
+        .boxed()
+}
+
+const PLACES: [&str; 3] = ["England", "San Tropez", "Antarctica"];
+
+fn places() -> Vec<(&'static str, String)> {
+    PLACES
+        .into_iter()
+        .map(|item| (item, item.to_owned()))
+        .collect()
+}
+
+fn places_dropdown() -> impl Widget<PlaceProps> {
+    let header = Button::new("Select place")
+        .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(DROPDOWN_SHOW));
+    Dropdown::new(header, |_, _| {
+        Flex::column().with_child(RadioGroup::row(places()).align_vertical(UnitPoint::CENTER))
+    })
+    .align_left()
+    .lens(PlaceProps::place)
+}
+
+fn places_select() -> impl Widget<PlaceProps> {
+    let header = Button::new("Select place from select")
+        .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(SELECT_SHOW));
+    Select::new(header, PLACES)
+        .align_left()
+        .lens(PlaceProps::place)
+}
+
+fn dropdown_page() -> Box<dyn Widget<GalleryState>> {
+    let variants = Flex::column()
+        .with_child(variant("Places", places_dropdown()))
+        .with_child(variant(
+            "Popover",
+            Popover::new(Button::new("Edit place"), |_, _| {
+                TextBox::new().lens(PlaceProps::place)
+            })
+            .with_close_button(),
+        ))
+        .with_child(variant(
+            "On hover",
+            Popover::new(Label::new("Hover me"), |place: &PlaceProps, _| {
+                Label::new(format!("Going to {}", place.place))
+            })
+            .with_trigger(PopoverTrigger::Hover),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+    let panel = Flex::column()
+        .with_child(Label::new("Selected place"))
+        .with_child(TextBox::new().lens(PlaceProps::place).expand_width())
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Dropdown", variants, panel)
+        .lens(GalleryState::dropdown)
+        .boxed()
+}
+
+fn select_page() -> Box<dyn Widget<GalleryState>> {
+    let variants = Flex::column()
+        .with_child(variant("Places", places_select()))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+    let panel = Flex::column()
+        .with_child(Label::new("Selected place"))
+        .with_child(TextBox::new().lens(PlaceProps::place).expand_width())
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Select", variants, panel)
+        .lens(GalleryState::select)
+        .boxed()
+}
+
+fn pickers_page() -> Box<dyn Widget<GalleryState>> {
+    let today = Date::new(2026, 10, 19).unwrap();
+    let variants = Flex::column()
+        .with_child(variant("Date", DatePicker::new().lens(PickerProps::date)))
+        .with_child(variant(
+            "Weekdays ahead",
+            DatePicker::date_time()
+                .with_min(today)
+                .with_disabled(|date| date.weekday() >= 5)
+                .lens(PickerProps::meeting),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+    let panel = Flex::column()
+        .with_child(Label::new(|pickers: &PickerProps, _: &Env| {
+            format!("Date: {}", pickers.date)
+        }))
+        .with_child(Label::new(|pickers: &PickerProps, _: &Env| {
+            format!("Meeting: {}", pickers.meeting)
+        }))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Pickers", variants, panel)
+        .lens(GalleryState::pickers)
+        .boxed()
+}
+
+fn sliders_page() -> Box<dyn Widget<GalleryState>> {
+    let volume = || {
+        Slider::<f64>::new()
+            .with_range(0.0, 100.0)
+            .with_step(5.0)
+            .with_value_tooltip(|volume| format!("{volume:.0}%"))
+    };
+    let variants = Flex::column()
+        .with_child(variant(
+            "Volume",
+            volume()
+                .with_ticks(25.0)
+                .lens(SliderProps::volume)
+                .fix_width(200.0),
+        ))
+        .with_child(variant(
+            "Price range",
+            Slider::new()
+                .with_range(0.0, 1000.0)
+                .with_step(10.0)
+                .with_value_tooltip(|price| format!("${price:.0}"))
+                .lens(SliderProps::price)
+                .fix_width(200.0),
+        ))
+        .with_child(variant(
+            "Number",
+            NumberInput::new()
+                .with_range(0.0, 100.0)
+                .with_step(5.0)
+                .with_unit("%")
+                .lens(SliderProps::volume)
+                .fix_width(200.0),
+        ))
+        .with_child(variant(
+            "Vertical",
+            volume()
+                .vertical()
+                .lens(SliderProps::volume)
+                .fix_height(120.0),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+    let panel = Flex::column()
+        .with_child(Label::new(|sliders: &SliderProps, _: &Env| {
+            format!("Volume: {:.0}%", sliders.volume)
+        }))
+        .with_child(Label::new(|sliders: &SliderProps, _: &Env| {
+            let (low, high) = sliders.price;
+            format!("Price: ${low:.0} to ${high:.0}")
+        }))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Sliders", variants, panel)
+        .lens(GalleryState::sliders)
+        .boxed()
+}
+
+fn tabs_page() -> Box<dyn Widget<GalleryState>> {
+    let tabs = Tabs::new(
+        |name: &String| name.clone(),
+        |_, _| {
+            Label::new(|name: &String, _: &Env| format!("Contents of {name}"))
+                .center()
+                .expand()
+        },
+    )
+    .closable()
+    .fix_height(300.0);
+    let panel = Flex::column()
+        .with_child(Label::new("Drag tabs to reorder them, Ctrl+Tab switches."))
+        .with_default_spacer()
+        .with_child(
+            Button::new("Open tab").on_click(|_, tabs: &mut TabsState<String>, _| {
+                tabs.tabs
+                    .push_back(format!("untitled-{}.rs", tabs.tabs.len()));
+                tabs.selected = tabs.tabs.len() - 1;
+            }),
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Tabs", tabs, panel).lens(GalleryState::tabs).boxed()
+}
+
+fn table_page() -> Box<dyn Widget<GalleryState>> {
+    let cell = |text: fn(&FileRow) -> String| {
+        move || Label::new(move |file: &FileRow, _: &Env| text(file)).padding((6.0, 2.0))
+    };
+    let table = Table::new(22.0)
+        .with_column(
+            Column::new("Name", cell(|file| file.name.clone()), 200.0)
+                .with_bounds(80.0, 400.0)
+                .sortable_by_key(|file: &FileRow| file.name.clone()),
+        )
+        .with_column(
+            Column::new("Kind", cell(|file| file.kind.to_owned()), 100.0)
+                .sortable_by_key(|file: &FileRow| file.kind),
+        )
+        .with_column(
+            Column::new("Size", cell(|file| format!("{} KB", file.size)), 100.0)
+                .sortable_by_key(|file: &FileRow| file.size),
+        )
+        .with_selection(SelectionMode::Multi)
+        .fix_height(300.0);
+    let panel = Flex::column()
+        .with_child(Label::new(
+            "Click a header to sort, Shift-click to sort by more columns.",
+        ))
+        .with_default_spacer()
+        .with_child(Label::new(|files: &TableState<FileRow>, _: &Env| {
+            format!("{} of {} selected", files.selected.len(), files.rows.len())
+        }))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Table", table, panel)
+        .lens(GalleryState::files)
+        .boxed()
+}
+
+fn tree_page() -> Box<dyn Widget<GalleryState>> {
+    // every node has a few more below it, which take a moment to "load"
+    let tree = Tree::new(|| Label::new(|name: &String, _: &Env| name.clone()))
+        .with_loader(|name, loader| {
+            let name = name.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(300));
+                let children = (0..3)
+                    .map(|index| TreeNode::unloaded(format!("{name}.{index}")))
+                    .collect();
+                loader.complete(children);
+            });
+        })
+        .fix_height(300.0);
+    let panel = Flex::column()
+        .with_child(Label::new("Children load when a node is first expanded."))
+        .with_default_spacer()
+        .with_child(Label::new(
+            |scene: &TreeState<String>, _: &Env| match scene.selected_node() {
+                Some(node) => format!("{} selected", node.item),
+                None => "Nothing selected".to_owned(),
+            },
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Tree", tree, panel).lens(GalleryState::scene).boxed()
+}
+
+fn list_page() -> Box<dyn Widget<GalleryState>> {
+    let list = VirtualList::new(
+        || Label::new(|line: &String, _: &Env| line.clone()).padding((6.0, 0.0)),
+        22.0,
+    )
+    .fix_height(300.0);
+    let panel = Flex::column()
+        .with_child(Label::new(|log: &VirtualListState<String>, _: &Env| {
+            format!("{} lines", log.items.len())
+        }))
+        .with_default_spacer()
+        .with_child(Label::new(
+            |log: &VirtualListState<String>, _: &Env| match log.selected {
+                Some(index) => format!("Line {index} selected"),
+                None => "Nothing selected".to_owned(),
+            },
+        ))
+        .with_default_spacer()
+        .with_child(Button::new("Jump to middle").on_click(
+            |ctx, log: &mut VirtualListState<String>, _| {
+                let middle = log.items.len() / 2;
+                log.selected = Some(middle);
+                ctx.submit_command(SCROLL_TO_INDEX.with(middle));
+            },
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("List", list, panel).lens(GalleryState::log).boxed()
+}
+
+fn popup_page() -> Box<dyn Widget<GalleryState>> {
+    let popup = Container::new(
+        Flex::column()
+            .with_child(Label::new("This is a popup!").with_text_size(24.0))
+            .with_spacer(20.0)
+            .with_child(
+                Button::new("Close").on_click(|_ctx, shown: &mut bool, _env| {
+                    *shown = false;
+                }),
+            )
+            .main_axis_alignment(MainAxisAlignment::Center),
+    )
+    .background(theme::POPUP_BACKGROUND)
+    .border(theme::BORDER, 2.0)
+    .rounded(theme::CORNER_RADIUS)
+    .fix_size(300.0, 200.0);
+
+    let stage = ZStack::new(SizedBox::empty().expand())
+        .with_centered_child(Either::new(
+            |shown: &bool, _env| *shown,
+            popup,
+            SizedBox::empty(),
+        ))
+        .fix_height(300.0);
+
+    let toast = |toast: fn() -> Toast| {
+        Button::new("Show")
+            .on_click(move |ctx, _: &mut bool, _| ctx.submit_command(SHOW_TOAST.with(toast())))
+    };
+    let panel = Flex::column()
+        .with_child(Toggle::new().with_label("Shown"))
+        .with_default_spacer()
+        .with_child(Label::new("Toasts"))
+        .with_child(variant(
+            "Info",
+            toast(|| Toast::info("Copied to clipboard")),
+        ))
+        .with_child(variant(
+            "Error",
+            toast(|| Toast::error("Could not reach the server").with_action("Retry", RETRY)),
+        ))
+        .cross_axis_alignment(CrossAxisAlignment::Start);
+
+    page("Popup", stage, panel)
+        .lens(GalleryState::show_popup)
+        .boxed()
+}
+
+fn ui_builder() -> impl Widget<GalleryState> {
+    let content = ViewSwitcher::new(
+        |data: &GalleryState, _env| data.page,
+        |page, _data, _env| match page {
+            Page::Button => button_page(),
+            Page::Dropdown => dropdown_page(),
+            Page::Select => select_page(),
+            Page::Pickers => pickers_page(),
+            Page::Sliders => sliders_page(),
+            Page::Tabs => tabs_page(),
+            Page::Table => table_page(),
+            Page::Tree => tree_page(),
+            Page::List => list_page(),
+            Page::Popup => popup_page(),
+        },
+    );
+
+    let ui = Flex::row()
+        .with_child(sidebar().border(theme::BORDER, 1.0))
+        .with_flex_child(content, 1.0)
+        .background(theme::WINDOW_BACKGROUND);
+    let ui = ToastHost::new(ui);
+    ThemeScope::new(|data: &GalleryState| data.theme, ui)
+        .with_transition(Duration::from_millis(200))
+}
+
+fn main() -> Result<(), PlatformError> {
+    let main_window = WindowDesc::new(ui_builder())
+        .set_position(Point::new(300.0, 300.0))
+        .title("Druid Gio Widgets Gallery")
+        .window_size(Size::new(1200.0, 700.0));
+
+    let initial_state = GalleryState {
+        page: Page::Button,
+        theme: Preset::Dark,
+        button: ButtonProps {
+            label: "Advanced Button".to_owned(),
+            disabled: false,
+            surface: Color::rgb8(0x2b, 0x2d, 0x30),
+            accent: Color::rgb8(0x34, 0x74, 0xf0),
+            sections: OrdSet::unit(0),
+        },
+        dropdown: PlaceProps {
+            place: "California".to_owned(),
+        },
+        select: PlaceProps {
+            place: "California".to_owned(),
+        },
+        pickers: PickerProps {
+            date: Date::new(2026, 10, 19).unwrap(),
+            meeting: DateTime {
+                date: Date::new(2026, 10, 20).unwrap(),
+                time: Time::new(9, 30).unwrap(),
+            },
+        },
+        sliders: SliderProps {
+            volume: 40.0,
+            price: (200.0, 650.0),
+        },
+        tabs: TabsState::new(
+            ["main.rs", "lib.rs", "theme.rs", "popover.rs", "toast.rs"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        ),
+        files: TableState::new(
+            (0..5_000)
+                .map(|index| FileRow {
+                    name: format!("file-{index:04}"),
+                    kind: ["Image", "Text", "Audio"][index % 3],
+                    size: (index as u64 * 7919) % 4096,
+                })
+                .collect(),
+        ),
+        scene: TreeState::new(
+            ["camera", "light", "mesh"]
+                .into_iter()
+                .map(|name| TreeNode::unloaded(name.to_owned()))
+                .collect(),
+        ),
+        log: VirtualListState::new(
+            (0..100_000)
+                .map(|line| format!("{line:>6}  request served in {} ms", line % 97))
+                .collect(),
+        ),
+        show_popup: false,
+    };
+
+    AppLauncher::with_window(main_window)
+        .configure_env(theme::dark)
+        .log_to_console()
+        .launch(initial_state)
+}
@@ -0,0 +1,475 @@
+//! Tweened values, easing curves and timelines, advanced by druid's `AnimFrame`s.
+//!
+//! Widgets keep an [`Animated`] value per animated property, retarget it when their state
+//! changes and advance it on `Event::AnimFrame`. [`Animate`] does the same for a value
+//! handed to a child through the `Env`. Everything jumps straight to its target while
+//! [`reduced_motion`] is on.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use druid::widget::prelude::*;
+use druid::{Color, Insets, Key, Point, ValueType, WidgetPod};
+
+static REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Turns animations off everywhere, for users who asked for reduced motion.
+pub fn set_reduced_motion(reduced: bool) {
+    REDUCED_MOTION.store(reduced, Ordering::Relaxed);
+}
+
+pub fn reduced_motion() -> bool {
+    REDUCED_MOTION.load(Ordering::Relaxed)
+}
+
+/// A value that can be interpolated.
+pub trait Tween: Clone {
+    /// The value `t` of the way from `self` to `to`, `t` may leave 0..1 for springs.
+    fn tween(&self, to: &Self, t: f64) -> Self;
+}
+
+impl Tween for f64 {
+    fn tween(&self, to: &f64, t: f64) -> f64 {
+        self + (to - self) * t
+    }
+}
+
+impl Tween for Color {
+    fn tween(&self, to: &Color, t: f64) -> Color {
+        let (r0, g0, b0, a0) = self.as_rgba();
+        let (r1, g1, b1, a1) = to.as_rgba();
+        // rgba clamps, so springs overshooting stay valid colors
+        Color::rgba(
+            r0.tween(&r1, t),
+            g0.tween(&g1, t),
+            b0.tween(&b1, t),
+            a0.tween(&a1, t),
+        )
+    }
+}
+
+impl Tween for Point {
+    fn tween(&self, to: &Point, t: f64) -> Point {
+        self.lerp(*to, t)
+    }
+}
+
+impl Tween for Size {
+    fn tween(&self, to: &Size, t: f64) -> Size {
+        Size::new(
+            self.width.tween(&to.width, t),
+            self.height.tween(&to.height, t),
+        )
+    }
+}
+
+impl Tween for Insets {
+    fn tween(&self, to: &Insets, t: f64) -> Insets {
+        Insets::new(
+            self.x0.tween(&to.x0, t),
+            self.y0.tween(&to.y0, t),
+            self.x1.tween(&to.x1, t),
+            self.y1.tween(&to.y1, t),
+        )
+    }
+}
+
+/// Maps the linear progress of an animation to the progress of its value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    /// A CSS-style `cubic-bezier(x1, y1, x2, y2)`
+    CubicBezier(f64, f64, f64, f64),
+    /// A damped spring pulled to the target, time measured in animation durations
+    Spring {
+        stiffness: f64,
+        damping: f64,
+    },
+}
+
+impl Easing {
+    pub const EASE: Easing = Easing::CubicBezier(0.25, 0.1, 0.25, 1.);
+    pub const EASE_IN: Easing = Easing::CubicBezier(0.42, 0., 1., 1.);
+    pub const EASE_OUT: Easing = Easing::CubicBezier(0., 0., 0.58, 1.);
+    pub const EASE_IN_OUT: Easing = Easing::CubicBezier(0.42, 0., 0.58, 1.);
+    /// Settles within the duration, overshooting a little
+    pub const SPRING: Easing = Easing::Spring {
+        stiffness: 100.,
+        damping: 12.,
+    };
+
+    pub fn ease(&self, t: f64) -> f64 {
+        if t <= 0. {
+            return 0.;
+        }
+        if t >= 1. {
+            return 1.;
+        }
+        match *self {
+            Easing::Linear => t,
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+            Easing::Spring { stiffness, damping } => spring(stiffness, damping, t),
+        }
+    }
+}
+
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    // both curves run from 0 to 1, with control points at `a` and `b`
+    let curve = |a: f64, b: f64, s: f64| {
+        let r = 1. - s;
+        3. * r * r * s * a + 3. * r * s * s * b + s * s * s
+    };
+    let slope = |a: f64, b: f64, s: f64| {
+        let r = 1. - s;
+        3. * r * r * a + 6. * r * s * (b - a) + 3. * s * s * (1. - b)
+    };
+
+    // Newton first, it converges in a few steps for sane curves
+    let mut s = x;
+    for _ in 0..8 {
+        let error = curve(x1, x2, s) - x;
+        if error.abs() < 1e-7 {
+            return curve(y1, y2, s);
+        }
+        let d = slope(x1, x2, s);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s -= error / d;
+    }
+
+    // bisection for flat spots
+    let (mut lo, mut hi) = (0., 1.);
+    s = x;
+    for _ in 0..32 {
+        if curve(x1, x2, s) < x {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = (lo + hi) / 2.;
+    }
+    curve(y1, y2, s)
+}
+
+fn spring(stiffness: f64, damping: f64, t: f64) -> f64 {
+    // unit mass released at 0 without velocity, pulled to 1
+    let omega = stiffness.sqrt();
+    let zeta = damping / (2. * omega);
+    if zeta < 1. {
+        let omega_d = omega * (1. - zeta * zeta).sqrt();
+        let decay = (-zeta * omega * t).exp();
+        1. - decay * ((omega_d * t).cos() + zeta * omega / omega_d * (omega_d * t).sin())
+    } else if zeta == 1. {
+        1. - (-omega * t).exp() * (1. + omega * t)
+    } else {
+        let root = (zeta * zeta - 1.).sqrt();
+        let r1 = -omega * (zeta - root);
+        let r2 = -omega * (zeta + root);
+        1. - (r2 * (r1 * t).exp() - r1 * (r2 * t).exp()) / (r2 - r1)
+    }
+}
+
+/// A value easing towards a target it can be pointed at any time.
+#[derive(Debug, Clone)]
+pub struct Animated<V> {
+    from: V,
+    to: V,
+    /// `None` at rest
+    elapsed: Option<Duration>,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<V: Tween> Animated<V> {
+    /// Starts at rest at `value`.
+    pub fn new(value: V) -> Self {
+        Animated {
+            from: value.clone(),
+            to: value,
+            elapsed: None,
+            duration: Duration::from_millis(150),
+            easing: Easing::EASE_OUT,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Starts moving from the current value to `to`, returns whether there is anything
+    /// to animate.
+    pub fn animate_to(&mut self, to: V) -> bool {
+        if reduced_motion() || self.duration.is_zero() {
+            self.jump_to(to);
+            return false;
+        }
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = Some(Duration::ZERO);
+        true
+    }
+
+    /// Moves to `to` without animating.
+    pub fn jump_to(&mut self, to: V) {
+        self.from = to.clone();
+        self.to = to;
+        self.elapsed = None;
+    }
+
+    /// Moves time forward, returns whether the animation still runs.
+    pub fn advance(&mut self, nanos: u64) -> bool {
+        if let Some(elapsed) = self.elapsed {
+            let elapsed = elapsed + Duration::from_nanos(nanos);
+            self.elapsed = (elapsed < self.duration).then_some(elapsed);
+        }
+        self.is_running()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.elapsed.is_some()
+    }
+
+    pub fn value(&self) -> V {
+        match self.elapsed {
+            Some(elapsed) => {
+                let t = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+                self.from.tween(&self.to, self.easing.ease(t))
+            }
+            None => self.to.clone(),
+        }
+    }
+
+    pub fn target(&self) -> &V {
+        &self.to
+    }
+}
+
+struct Segment<V> {
+    to: V,
+    duration: Duration,
+    easing: Easing,
+}
+
+/// A sequence of tweens played one after the other.
+///
+/// ```ignore
+/// let pulse = Timeline::new(1.)
+///     .then(1.2, Duration::from_millis(80), Easing::EASE_OUT)
+///     .then(1., Duration::from_millis(200), Easing::SPRING);
+/// ```
+pub struct Timeline<V> {
+    start: V,
+    segments: Vec<Segment<V>>,
+    elapsed: Duration,
+}
+
+impl<V: Tween> Timeline<V> {
+    pub fn new(start: V) -> Self {
+        Timeline {
+            start,
+            segments: Vec::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Appends a tween from wherever the timeline ended to `to`.
+    pub fn then(mut self, to: V, duration: Duration, easing: Easing) -> Self {
+        self.segments.push(Segment {
+            to,
+            duration,
+            easing,
+        });
+        self
+    }
+
+    /// Holds the last value for `duration`.
+    pub fn wait(self, duration: Duration) -> Self {
+        let to = self.end().clone();
+        self.then(to, duration, Easing::Linear)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.segments.iter().map(|segment| segment.duration).sum()
+    }
+
+    fn end(&self) -> &V {
+        self.segments
+            .last()
+            .map_or(&self.start, |segment| &segment.to)
+    }
+
+    /// The value `at` into the timeline.
+    pub fn value_at(&self, at: Duration) -> V {
+        let mut from = &self.start;
+        let mut left = at;
+        for segment in &self.segments {
+            if left < segment.duration {
+                let t = left.as_secs_f64() / segment.duration.as_secs_f64();
+                return from.tween(&segment.to, segment.easing.ease(t));
+            }
+            left -= segment.duration;
+            from = &segment.to;
+        }
+        self.end().clone()
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = if reduced_motion() {
+            self.duration()
+        } else {
+            Duration::ZERO
+        };
+    }
+
+    /// Moves time forward, returns whether the timeline still runs.
+    pub fn advance(&mut self, nanos: u64) -> bool {
+        self.elapsed = (self.elapsed + Duration::from_nanos(nanos)).min(self.duration());
+        self.is_running()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.elapsed < self.duration()
+    }
+
+    pub fn value(&self) -> V {
+        self.value_at(self.elapsed)
+    }
+}
+
+/// Animates an `Env` value for its child, following a target derived from the data.
+///
+/// It only requests animation frames while the value moves.
+///
+/// ```ignore
+/// const GLOW: Key<Color> = Key::new("app.glow");
+/// let glow = Painter::new(|ctx, _, env| ctx.fill(ctx.size().to_rect(), &env.get(GLOW)));
+/// Animate::new(GLOW, |on: &bool, _| if *on { Color::WHITE } else { Color::BLACK }, glow)
+/// ```
+pub struct Animate<T, V, W> {
+    key: Key<V>,
+    target: TargetFn<T, V>,
+    value: Option<Animated<V>>,
+    duration: Duration,
+    easing: Easing,
+    child: WidgetPod<T, W>,
+    resolved: Option<Resolved<V>>,
+}
+
+type TargetFn<T, V> = Box<dyn Fn(&T, &Env) -> V>;
+
+/// The `Env` last handed to the child, along with what it was built from.
+struct Resolved<V> {
+    parent: Env,
+    value: V,
+    env: Env,
+}
+
+impl<T, V: Tween, W: Widget<T>> Animate<T, V, W> {
+    pub fn new(key: Key<V>, target: impl Fn(&T, &Env) -> V + 'static, child: W) -> Self {
+        Animate {
+            key,
+            target: Box::new(target),
+            value: None,
+            duration: Duration::from_millis(150),
+            easing: Easing::EASE_OUT,
+            child: WidgetPod::new(child),
+            resolved: None,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl<T, V: Tween + ValueType + PartialEq, W> Animate<T, V, W> {
+    /// `env` with the current value added, only rebuilt when the value or `env` itself
+    /// changed since the last call, so the child doesn't see a new `Env` on every pass.
+    fn env(&mut self, env: &Env, data: &T) -> Env {
+        let value = match &self.value {
+            Some(value) => value.value(),
+            None => (self.target)(data, env),
+        };
+        if let Some(resolved) = &self.resolved
+            && resolved.value == value
+            && resolved.parent.same(env)
+        {
+            return resolved.env.clone();
+        }
+
+        let animated = env.clone().adding(self.key.clone(), value.clone());
+        self.resolved = Some(Resolved {
+            parent: env.clone(),
+            value,
+            env: animated.clone(),
+        });
+        animated
+    }
+}
+
+impl<T: Data, V: Tween + ValueType + PartialEq, W: Widget<T>> Widget<T> for Animate<T, V, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::AnimFrame(nanos) = event
+            && let Some(value) = &mut self.value
+            && value.is_running()
+        {
+            if value.advance(*nanos) {
+                ctx.request_anim_frame();
+            }
+            // the child reads the new value in `update`
+            ctx.request_update();
+            ctx.request_paint();
+        }
+        let env = self.env(env, data);
+        self.child.event(ctx, event, data, &env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.value = Some(
+                Animated::new((self.target)(data, env))
+                    .with_duration(self.duration)
+                    .with_easing(self.easing),
+            );
+        }
+        let env = self.env(env, data);
+        self.child.lifecycle(ctx, event, data, &env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let target = (self.target)(data, env);
+        if let Some(value) = &mut self.value
+            && *value.target() != target
+            && value.animate_to(target)
+        {
+            ctx.request_anim_frame();
+        }
+        let env = self.env(env, data);
+        self.child.update(ctx, data, &env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let env = self.env(env, data);
+        let size = self.child.layout(ctx, bc, data, &env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let env = self.env(env, data);
+        self.child.paint(ctx, data, &env)
+    }
+}
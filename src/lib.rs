@@ -0,0 +1,50 @@
+pub mod animation;
+pub mod native;
+pub mod theme;
+pub mod widgets;
+
+/// Re-exports the widgets and selectors needed to build a UI with this crate.
+pub mod prelude {
+    #[cfg(feature = "button")]
+    pub use crate::widgets::advanced_button::AdvancedButton;
+    #[cfg(feature = "bus")]
+    pub use crate::widgets::bus::{Publish, Subscribe, Subscription};
+    #[cfg(feature = "color-picker")]
+    pub use crate::widgets::color_picker::{ColorEditor, ColorPicker};
+    #[cfg(feature = "date-picker")]
+    pub use crate::widgets::date_picker::{
+        Calendar, Date, DatePicker, DateTime, PickerValue, Time,
+    };
+    #[cfg(feature = "disclosure")]
+    pub use crate::widgets::disclosure::{Accordion, Disclosure};
+    #[cfg(feature = "dropdown")]
+    pub use crate::widgets::dropdown::{DROPDOWN_CLOSED, DROPDOWN_HIDE, DROPDOWN_SHOW, Dropdown};
+    #[cfg(feature = "number-input")]
+    pub use crate::widgets::number_input::{NumberInput, NumberValue};
+    #[cfg(feature = "popover")]
+    pub use crate::widgets::popover::{
+        POPOVER_CLOSED, POPOVER_HIDE, POPOVER_SHOW, Popover, PopoverTrigger,
+    };
+    #[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+    pub use crate::widgets::popup::{PopupAnimation, PopupTransition};
+    #[cfg(feature = "select")]
+    pub use crate::widgets::select::{SELECT_CLOSED, SELECT_HIDE, SELECT_SHOW, Select};
+    #[cfg(feature = "slider")]
+    pub use crate::widgets::slider::{Slider, SliderValue};
+    #[cfg(feature = "table")]
+    pub use crate::widgets::table::{Column, SelectionMode, SortKey, Table, TableState};
+    #[cfg(feature = "tabs")]
+    pub use crate::widgets::tabs::{Tabs, TabsState};
+    #[cfg(feature = "toast")]
+    pub use crate::widgets::toast::{SHOW_TOAST, Severity, Toast, ToastCorner, ToastHost};
+    #[cfg(feature = "toggle")]
+    pub use crate::widgets::toggle::{LabelPlacement, Toggle};
+    #[cfg(feature = "tree")]
+    pub use crate::widgets::tree::{ChildLoader, Tree, TreeNode, TreePath, TreeState};
+    #[cfg(feature = "virtual-list")]
+    pub use crate::widgets::virtual_list::{
+        RowHeight, SCROLL_TO_INDEX, VirtualList, VirtualListState,
+    };
+
+    pub use crate::{keys, match_command, selectors};
+}
@@ -0,0 +1,22 @@
+use druid::widget::prelude::*;
+use druid::{Menu, MenuItem, Point, WindowHandle};
+use tracing::trace;
+
+use super::ContextMenuItem;
+
+pub(super) fn show_context_menu<T: Data>(
+    ctx: &mut EventCtx,
+    items: &[ContextMenuItem],
+    location: Point,
+) {
+    let target = ctx.widget_id();
+    let menu = items.iter().fold(Menu::empty(), |menu, item| {
+        menu.entry(MenuItem::new(item.title.clone()).command(item.selector.to(target)))
+    });
+    let location = ctx.to_window(location);
+    ctx.show_context_menu::<T>(menu, location);
+}
+
+pub(super) fn make_titlebar_transparent(_window: &WindowHandle) {
+    trace!("transparent titlebar is only supported on macOS");
+}
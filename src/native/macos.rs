@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+
+use cocoa::appkit::{NSWindow, NSWindowStyleMask};
+use cocoa::base::{YES, id, nil};
+use cocoa::foundation::{NSAutoreleasePool, NSPoint, NSRect, NSString};
+use druid::widget::prelude::*;
+use druid::{ExtEventSink, HasRawWindowHandle, Point, RawWindowHandle, Selector, WindowHandle};
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use tracing::warn;
+
+use super::ContextMenuItem;
+
+// The menu the user is currently interacting with, `popUpMenuPositioningItem` blocks
+// until the menu is dismissed so there is at most one.
+struct OpenMenu {
+    sink: ExtEventSink,
+    target: WidgetId,
+    selectors: Vec<Selector>,
+}
+
+thread_local! {
+    static OPEN_MENU: RefCell<Option<OpenMenu>> = const { RefCell::new(None) };
+}
+
+pub(super) fn show_context_menu<T: Data>(
+    ctx: &mut EventCtx,
+    items: &[ContextMenuItem],
+    location: Point,
+) {
+    let location = ctx.to_screen(location);
+    OPEN_MENU.with(|menu| {
+        *menu.borrow_mut() = Some(OpenMenu {
+            sink: ctx.get_external_handle(),
+            target: ctx.widget_id(),
+            selectors: items.iter().map(|item| item.selector).collect(),
+        })
+    });
+
+    unsafe {
+        // the menu and its items are autoreleased once the run loop gets back to druid,
+        // the menu only holds its items' target weakly so the handler is released by hand
+        let handler: id = msg_send![menu_handler_class(), new];
+        let menu: id = msg_send![class!(NSMenu), alloc];
+        let menu: id = msg_send![menu, initWithTitle: empty_string()];
+        let menu = menu.autorelease();
+
+        for (tag, item) in items.iter().enumerate() {
+            let entry: id = msg_send![class!(NSMenuItem), alloc];
+            let entry: id = msg_send![entry,
+                initWithTitle: NSString::alloc(nil).init_str(&item.title).autorelease()
+                action: sel!(menuItemClicked:)
+                keyEquivalent: empty_string()
+            ];
+            let _: () = msg_send![entry, setTag: tag as isize];
+            let _: () = msg_send![entry, setTarget: handler];
+            let _: () = msg_send![menu, addItem: entry.autorelease()];
+        }
+
+        // AppKit screen coordinates start at the bottom left of the main screen
+        let screen: id = msg_send![class!(NSScreen), mainScreen];
+        let frame: NSRect = msg_send![screen, frame];
+        let location = NSPoint::new(location.x, frame.size.height - location.y);
+
+        let _: () = msg_send![menu,
+            popUpMenuPositioningItem: nil
+            atLocation: location
+            inView: nil
+        ];
+        // the chosen item's action has run by the time the menu returns
+        let _: () = msg_send![handler, release];
+    }
+
+    OPEN_MENU.with(|menu| menu.borrow_mut().take());
+}
+
+unsafe fn empty_string() -> id {
+    unsafe { NSString::alloc(nil).init_str("").autorelease() }
+}
+
+/// Registers `GioMenuHandler`, whose `menuItemClicked:` forwards the chosen item to druid.
+fn menu_handler_class() -> &'static Class {
+    if let Some(class) = Class::get("GioMenuHandler") {
+        return class;
+    }
+
+    extern "C" fn menu_item_clicked(_this: &Object, _sel: Sel, item: id) {
+        let tag: isize = unsafe { msg_send![item, tag] };
+        OPEN_MENU.with(|menu| {
+            let menu = menu.borrow();
+            let Some(menu) = menu.as_ref() else {
+                return;
+            };
+            if let Some(selector) = menu.selectors.get(tag as usize)
+                && menu
+                    .sink
+                    .submit_command(*selector, (), menu.target)
+                    .is_err()
+            {
+                warn!("context menu item chosen after the app closed");
+            }
+        });
+    }
+
+    let mut decl = ClassDecl::new("GioMenuHandler", class!(NSObject)).unwrap();
+    unsafe {
+        decl.add_method(
+            sel!(menuItemClicked:),
+            menu_item_clicked as extern "C" fn(&Object, Sel, id),
+        );
+    }
+    decl.register()
+}
+
+pub(super) fn make_titlebar_transparent(window: &WindowHandle) {
+    window.show_titlebar(false);
+
+    let RawWindowHandle::AppKit(handle) = window.raw_window_handle() else {
+        return;
+    };
+    // the handle's ns_window isn't filled in, so ask the view for its window
+    if handle.ns_view.is_null() {
+        return;
+    }
+    unsafe {
+        let ns_window: id = msg_send![handle.ns_view as id, window];
+        if ns_window != nil {
+            ns_window.setTitlebarAppearsTransparent_(YES);
+
+            let mut style_mask = ns_window.styleMask();
+            style_mask.insert(NSWindowStyleMask::NSFullSizeContentViewWindowMask);
+            ns_window.setStyleMask_(style_mask);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Native platform integrations.
+//!
+//! With the `macos-native` feature on macOS these talk to AppKit directly. Everywhere else
+//! they fall back to what druid provides, so callers build the same code on every platform.
+
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{Point, Selector};
+
+#[cfg(all(feature = "macos-native", target_os = "macos"))]
+// objc's `msg_send!` checks cfgs rustc doesn't know about
+#[allow(unexpected_cfgs)]
+mod macos;
+#[cfg(all(feature = "macos-native", target_os = "macos"))]
+use macos as platform;
+
+#[cfg(not(all(feature = "macos-native", target_os = "macos")))]
+mod fallback;
+#[cfg(not(all(feature = "macos-native", target_os = "macos")))]
+use fallback as platform;
+
+/// An entry of a context menu, choosing it sends `selector` to the widget that opened the menu.
+#[derive(Debug, Clone)]
+pub struct ContextMenuItem {
+    pub title: String,
+    pub selector: Selector,
+}
+
+impl ContextMenuItem {
+    pub fn new(title: impl Into<String>, selector: Selector) -> ContextMenuItem {
+        ContextMenuItem {
+            title: title.into(),
+            selector,
+        }
+    }
+}
+
+/// Shows a context menu at `location`, in the coordinate space of the current widget.
+///
+/// `T` must be the application's root data type, like for [`EventCtx::show_context_menu`].
+/// On macOS with `macos-native` this is an `NSMenu`, otherwise druid's own context menu.
+pub fn show_context_menu<T: Data>(ctx: &mut EventCtx, items: &[ContextMenuItem], location: Point) {
+    platform::show_context_menu::<T>(ctx, items, location)
+}
+
+/// Makes the titlebar of the window containing this widget transparent, with the content
+/// drawn underneath it.
+///
+/// Only has an effect on macOS with `macos-native`, other platforms keep their titlebar.
+pub struct TransparentTitlebar;
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for TransparentTitlebar {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            platform::make_titlebar_transparent(ctx.window());
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
@@ -0,0 +1,442 @@
+//! Theme keys shared by every widget in the crate, and the light and dark presets filling them.
+//!
+//! Apply a preset to the whole app or to a subtree:
+//!
+//! ```ignore
+//! AppLauncher::with_window(window).configure_env(theme::dark);
+//! widget.env_scope(theme::light);
+//! ```
+//!
+//! To switch presets at runtime keep the [`Preset`] in the app data and wrap the UI in a
+//! [`ThemeScope`]. [`system::FollowSystem`] keeps that preset in line with the desktop.
+//!
+//! The presets also set druid's own theme keys, so druid's built-in widgets sit well next
+//! to ours. Widgets in this crate fall back to the dark preset for keys the `Env` lacks.
+
+pub mod system;
+
+use std::time::Duration;
+
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+use druid::WidgetExt;
+use druid::kurbo::RoundedRectRadii;
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{Color, FontDescriptor, FontFamily, Insets, Key, Point, ValueType, WidgetPod};
+
+crate::keys! {
+    /// Background of windows
+    WINDOW_BACKGROUND: Color,
+    /// Fill of controls such as buttons
+    SURFACE: Color,
+    /// Fill of disabled controls
+    SURFACE_DISABLED: Color,
+    /// Background of popup windows (dropdowns, selects)
+    POPUP_BACKGROUND: Color,
+    TEXT: Color,
+    TEXT_DISABLED: Color,
+    /// Border of controls at rest
+    BORDER: Color,
+    /// Border of focused and pressed controls, and selection highlights
+    ACCENT: Color,
+    /// Marks things that went well
+    SUCCESS: Color,
+    /// Marks things that need attention
+    WARNING: Color,
+    /// Marks failures and invalid input
+    ERROR: Color,
+    CORNER_RADIUS: RoundedRectRadii,
+    BORDER_WIDTH: f64,
+    /// Border width while a control is pressed
+    ACTIVE_BORDER_WIDTH: f64,
+    /// Gap between related controls
+    SPACING: f64,
+    /// Space between a control's border and its content
+    CONTROL_INSETS: Insets,
+    /// Space between a popup's border and its content
+    POPUP_INSETS: Insets,
+    FONT: FontDescriptor,
+}
+
+/// A complete set of theme values.
+#[derive(Debug, Clone, Copy, Data, PartialEq, Eq)]
+pub enum Preset {
+    Light,
+    Dark,
+}
+
+#[derive(Clone, Copy)]
+struct Palette {
+    window_background: Color,
+    surface: Color,
+    surface_disabled: Color,
+    popup_background: Color,
+    text: Color,
+    text_disabled: Color,
+    border: Color,
+    accent: Color,
+    success: Color,
+    warning: Color,
+    error: Color,
+}
+
+const DARK: Palette = Palette {
+    window_background: Color::rgb8(0x1e, 0x1f, 0x22),
+    surface: Color::rgb8(0x2b, 0x2d, 0x30),
+    surface_disabled: Color::rgb8(0x26, 0x28, 0x2b),
+    popup_background: Color::rgb8(0x2b, 0x2d, 0x30),
+    text: Color::rgb8(0xdf, 0xe1, 0xe5),
+    text_disabled: Color::rgb8(0x6f, 0x73, 0x7a),
+    border: Color::rgb8(0x51, 0x53, 0x55),
+    accent: Color::rgb8(0x34, 0x74, 0xf0),
+    success: Color::rgb8(0x5f, 0xb8, 0x65),
+    warning: Color::rgb8(0xf2, 0xc5, 0x5c),
+    error: Color::rgb8(0xf7, 0x54, 0x64),
+};
+
+const LIGHT: Palette = Palette {
+    window_background: Color::rgb8(0xf7, 0xf8, 0xfa),
+    surface: Color::rgb8(0xff, 0xff, 0xff),
+    surface_disabled: Color::rgb8(0xf2, 0xf3, 0xf5),
+    popup_background: Color::rgb8(0xff, 0xff, 0xff),
+    text: Color::rgb8(0x1e, 0x1f, 0x22),
+    text_disabled: Color::rgb8(0xa8, 0xad, 0xbd),
+    border: Color::rgb8(0xc9, 0xcc, 0xd6),
+    accent: Color::rgb8(0x35, 0x74, 0xf0),
+    success: Color::rgb8(0x20, 0x8a, 0x3c),
+    warning: Color::rgb8(0xc2, 0x7d, 0x04),
+    error: Color::rgb8(0xdb, 0x3b, 0x4b),
+};
+
+const CORNER: f64 = 4.;
+const FONT_SIZE: f64 = 13.;
+
+impl Preset {
+    /// Sets every crate theme key, and the matching druid keys, to this preset's values.
+    pub fn apply(self, env: &mut Env) {
+        let palette = self.palette();
+        set_crate_keys(palette, env);
+        set_druid_keys(palette, env);
+    }
+
+    fn palette(self) -> &'static Palette {
+        match self {
+            Preset::Light => &LIGHT,
+            Preset::Dark => &DARK,
+        }
+    }
+}
+
+impl Palette {
+    /// Blends towards `to`, `t` going from 0 (`self`) to 1 (`to`).
+    fn mix(&self, to: &Palette, t: f64) -> Palette {
+        let mix = |a: Color, b: Color| {
+            let (r0, g0, b0, a0) = a.as_rgba();
+            let (r1, g1, b1, a1) = b.as_rgba();
+            Color::rgba(
+                r0 + (r1 - r0) * t,
+                g0 + (g1 - g0) * t,
+                b0 + (b1 - b0) * t,
+                a0 + (a1 - a0) * t,
+            )
+        };
+        Palette {
+            window_background: mix(self.window_background, to.window_background),
+            surface: mix(self.surface, to.surface),
+            surface_disabled: mix(self.surface_disabled, to.surface_disabled),
+            popup_background: mix(self.popup_background, to.popup_background),
+            text: mix(self.text, to.text),
+            text_disabled: mix(self.text_disabled, to.text_disabled),
+            border: mix(self.border, to.border),
+            accent: mix(self.accent, to.accent),
+            success: mix(self.success, to.success),
+            warning: mix(self.warning, to.warning),
+            error: mix(self.error, to.error),
+        }
+    }
+}
+
+fn font() -> FontDescriptor {
+    FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(FONT_SIZE)
+}
+
+fn set_crate_keys(p: &Palette, env: &mut Env) {
+    env.set(WINDOW_BACKGROUND, p.window_background);
+    env.set(SURFACE, p.surface);
+    env.set(SURFACE_DISABLED, p.surface_disabled);
+    env.set(POPUP_BACKGROUND, p.popup_background);
+    env.set(TEXT, p.text);
+    env.set(TEXT_DISABLED, p.text_disabled);
+    env.set(BORDER, p.border);
+    env.set(ACCENT, p.accent);
+    env.set(SUCCESS, p.success);
+    env.set(WARNING, p.warning);
+    env.set(ERROR, p.error);
+    env.set(CORNER_RADIUS, CORNER);
+    env.set(BORDER_WIDTH, 2.);
+    env.set(ACTIVE_BORDER_WIDTH, 4.);
+    env.set(SPACING, 8.);
+    env.set(CONTROL_INSETS, Insets::uniform_xy(8., 2.));
+    env.set(POPUP_INSETS, Insets::uniform(6.));
+    env.set(FONT, font());
+}
+
+fn set_druid_keys(p: &Palette, env: &mut Env) {
+    use druid::theme;
+
+    env.set(theme::WINDOW_BACKGROUND_COLOR, p.window_background);
+    env.set(theme::TEXT_COLOR, p.text);
+    env.set(theme::DISABLED_TEXT_COLOR, p.text_disabled);
+    env.set(theme::PLACEHOLDER_COLOR, p.text_disabled);
+    env.set(theme::CURSOR_COLOR, p.text);
+    env.set(theme::FOREGROUND_LIGHT, p.text);
+    env.set(theme::FOREGROUND_DARK, p.text);
+    env.set(theme::DISABLED_FOREGROUND_LIGHT, p.text_disabled);
+    env.set(theme::DISABLED_FOREGROUND_DARK, p.text_disabled);
+    env.set(theme::BACKGROUND_LIGHT, p.surface);
+    env.set(theme::BACKGROUND_DARK, p.window_background);
+    env.set(theme::BUTTON_LIGHT, p.surface);
+    env.set(theme::BUTTON_DARK, p.surface);
+    env.set(theme::DISABLED_BUTTON_LIGHT, p.surface_disabled);
+    env.set(theme::DISABLED_BUTTON_DARK, p.surface_disabled);
+    env.set(theme::BORDER_DARK, p.border);
+    env.set(theme::BORDER_LIGHT, p.border);
+    env.set(theme::PRIMARY_LIGHT, p.accent);
+    env.set(theme::PRIMARY_DARK, p.accent);
+    env.set(theme::SELECTED_TEXT_BACKGROUND_COLOR, p.accent);
+    env.set(theme::BUTTON_BORDER_RADIUS, CORNER);
+    env.set(theme::TEXTBOX_BORDER_RADIUS, CORNER);
+    env.set(theme::TEXT_SIZE_NORMAL, FONT_SIZE);
+    env.set(theme::UI_FONT, font());
+}
+
+/// Applies [`Preset::Light`], shaped for `configure_env` and `env_scope`.
+pub fn light<T>(env: &mut Env, _data: &T) {
+    Preset::Light.apply(env);
+}
+
+/// Applies [`Preset::Dark`], shaped for `configure_env` and `env_scope`.
+pub fn dark<T>(env: &mut Env, _data: &T) {
+    Preset::Dark.apply(env);
+}
+
+thread_local! {
+    static FALLBACK: Env = {
+        let mut env = Env::empty();
+        set_crate_keys(&DARK, &mut env);
+        env
+    };
+}
+
+/// Reads a theme key, falling back to the dark preset when no preset was applied.
+pub fn get<V: ValueType>(env: &Env, key: Key<V>) -> V {
+    env.try_get(&key)
+        .unwrap_or_else(|_| FALLBACK.with(|fallback| fallback.get(&key)))
+}
+
+/// `env` with the crate keys filled in from the dark preset if no preset was applied.
+///
+/// Widgets handed a key directly (`Container::background(POPUP_BACKGROUND)`) resolve it
+/// with `Env::get`, so windows we open get a completed `Env`.
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+pub(crate) fn completed(env: &Env) -> Env {
+    let mut env = env.clone();
+    complete(&mut env);
+    env
+}
+
+/// Fills in the crate keys from the dark preset if no preset was applied, shaped for
+/// `env_scope` on content we build ourselves.
+#[cfg(any(
+    feature = "dropdown",
+    feature = "popover",
+    feature = "select",
+    feature = "toast"
+))]
+pub(crate) fn complete(env: &mut Env) {
+    if env.try_get(SURFACE).is_err() {
+        set_crate_keys(&DARK, env);
+    }
+}
+
+/// Wraps the content of a popup window in the themed popup background and border.
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+pub(crate) fn popup_frame<T: Data>(content: impl Widget<T> + 'static) -> impl Widget<T> {
+    content
+        .padding(POPUP_INSETS)
+        .background(POPUP_BACKGROUND)
+        .rounded(CORNER_RADIUS)
+        .border(BORDER, 1.)
+        // the parent window can hand the popup a new `Env` at any time, keep it complete
+        .env_scope(|env, _| complete(env))
+        .controller(RefreshPopup)
+}
+
+crate::selectors! {
+    /// Sent to a popup window after its parent's `Env` changed.
+    ///
+    /// druid hands the popup the new `Env` on its own, but nothing in the popup updates
+    /// until something asks it to.
+    #[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+    pub(crate) REFRESH_POPUP,
+}
+
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+struct RefreshPopup;
+
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+impl<T: Data, W: Widget<T>> Controller<T, W> for RefreshPopup {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event
+            && cmd.is(REFRESH_POPUP)
+        {
+            ctx.request_update();
+            ctx.request_layout();
+            ctx.request_paint();
+            ctx.set_handled();
+            return;
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+/// Applies the preset held in the app data to its child.
+///
+/// Switching the preset re-resolves the `Env` of the child and of every popup opened
+/// under it, optionally cross-fading the colors.
+///
+/// ```ignore
+/// ThemeScope::new(|data: &AppState| data.theme, ui).with_transition(Duration::from_millis(200))
+/// ```
+pub struct ThemeScope<T, W> {
+    preset: Box<dyn Fn(&T) -> Preset>,
+    child: WidgetPod<T, W>,
+    transition: Duration,
+    fade: Option<Fade>,
+    resolved: Option<Resolved>,
+}
+
+struct Fade {
+    from: Palette,
+    elapsed: Duration,
+}
+
+/// The `Env` last handed to the child, along with what it was built from.
+struct Resolved {
+    parent: Env,
+    preset: Preset,
+    /// How far the fade had gone, if one was running
+    faded: Option<Duration>,
+    env: Env,
+}
+
+impl<T: Data, W: Widget<T>> ThemeScope<T, W> {
+    pub fn new(preset: impl Fn(&T) -> Preset + 'static, child: W) -> Self {
+        ThemeScope {
+            preset: Box::new(preset),
+            child: WidgetPod::new(child),
+            transition: Duration::ZERO,
+            fade: None,
+            resolved: None,
+        }
+    }
+
+    /// Cross-fades the colors over `duration` when the preset changes.
+    pub fn with_transition(mut self, duration: Duration) -> Self {
+        self.transition = duration;
+        self
+    }
+
+    /// The palette shown right now for `target`, halfway through a fade if one runs.
+    fn palette(&self, target: Preset) -> Palette {
+        let to = target.palette();
+        match &self.fade {
+            Some(fade) => {
+                let t = fade.elapsed.as_secs_f64() / self.transition.as_secs_f64();
+                // smoothstep
+                fade.from.mix(to, t * t * (3. - 2. * t))
+            }
+            None => *to,
+        }
+    }
+
+    /// `env` with the palette applied, only rebuilt when the preset, the fade or `env`
+    /// itself changed since the last call.
+    fn env(&mut self, env: &Env, data: &T) -> Env {
+        let preset = (self.preset)(data);
+        let faded = self.fade.as_ref().map(|fade| fade.elapsed);
+        if let Some(resolved) = &self.resolved
+            && resolved.preset == preset
+            && resolved.faded == faded
+            && resolved.parent.same(env)
+        {
+            return resolved.env.clone();
+        }
+
+        let mut themed = env.clone();
+        let palette = self.palette(preset);
+        set_crate_keys(&palette, &mut themed);
+        set_druid_keys(&palette, &mut themed);
+        self.resolved = Some(Resolved {
+            parent: env.clone(),
+            preset,
+            faded,
+            env: themed.clone(),
+        });
+        themed
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for ThemeScope<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::AnimFrame(nanos) = event
+            && let Some(fade) = &mut self.fade
+        {
+            fade.elapsed += Duration::from_nanos(*nanos);
+            if fade.elapsed >= self.transition {
+                self.fade = None;
+            } else {
+                ctx.request_anim_frame();
+            }
+            // the new colors reach the children through `update`
+            ctx.request_update();
+            ctx.request_paint();
+        }
+        let env = self.env(env, data);
+        self.child.event(ctx, event, data, &env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let env = self.env(env, data);
+        self.child.lifecycle(ctx, event, data, &env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let old = (self.preset)(old_data);
+        if old != (self.preset)(data) {
+            if !self.transition.is_zero() {
+                self.fade = Some(Fade {
+                    from: self.palette(old),
+                    elapsed: Duration::ZERO,
+                });
+                ctx.request_anim_frame();
+            }
+            ctx.request_paint();
+        }
+        let env = self.env(env, data);
+        self.child.update(ctx, data, &env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let env = self.env(env, data);
+        let size = self.child.layout(ctx, bc, data, &env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let env = self.env(env, data);
+        self.child.paint(ctx, data, &env)
+    }
+}
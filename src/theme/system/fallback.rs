@@ -0,0 +1,12 @@
+use super::ColorScheme;
+
+pub(super) struct Watch;
+
+pub(super) fn color_scheme() -> ColorScheme {
+    ColorScheme::NoPreference
+}
+
+pub(super) fn watch(report: impl Fn(ColorScheme) + Clone + Send + 'static) -> Watch {
+    report(ColorScheme::NoPreference);
+    Watch
+}
@@ -0,0 +1,220 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+
+use gio::glib::{MainContext, SignalHandlerId, ToVariant, Variant, VariantTy};
+use gio::prelude::*;
+use gio::{
+    BusType, Cancellable, DBusCallFlags, DBusConnection, DBusSignalFlags, SignalSubscriptionId,
+};
+use tracing::debug;
+
+use super::ColorScheme;
+
+const PORTAL: &str = "org.freedesktop.portal.Desktop";
+const PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS: &str = "org.freedesktop.portal.Settings";
+const NAMESPACE: &str = "org.freedesktop.appearance";
+const KEY: &str = "color-scheme";
+const TIMEOUT_MS: i32 = 1000;
+// read by name, `gtk::prelude` can't bring in `SettingsExt` next to the one from gio
+const GTK_THEME_NAME: &str = "gtk-theme-name";
+const GTK_PREFER_DARK: &str = "gtk-application-prefer-dark-theme";
+
+impl ColorScheme {
+    fn from_portal(value: u32) -> ColorScheme {
+        match value {
+            1 => ColorScheme::Dark,
+            2 => ColorScheme::Light,
+            _ => ColorScheme::NoPreference,
+        }
+    }
+}
+
+/// Reads the color scheme from the freedesktop settings portal.
+#[derive(Clone)]
+pub struct Portal {
+    connection: DBusConnection,
+}
+
+impl Portal {
+    /// The portal on the session bus.
+    pub fn session() -> Option<Portal> {
+        let connection = gio::bus_get_sync(BusType::Session, Cancellable::NONE)
+            .map_err(|err| debug!(%err, "no session bus"))
+            .ok()?;
+        Some(Portal::on(connection))
+    }
+
+    /// The portal on any bus, tests point this at a bus of their own.
+    pub fn on(connection: DBusConnection) -> Portal {
+        Portal { connection }
+    }
+
+    /// `None` when no portal answers or it doesn't know the setting.
+    pub fn color_scheme(&self) -> Option<ColorScheme> {
+        // `ReadOne` is version 2 of the interface, `Read` wraps the value in one more variant
+        let value = self
+            .read("ReadOne")
+            .or_else(|| self.read("Read").and_then(|value| value.as_variant()))?;
+        value.get::<u32>().map(ColorScheme::from_portal)
+    }
+
+    fn read(&self, method: &str) -> Option<Variant> {
+        let reply = self
+            .connection
+            .call_sync(
+                Some(PORTAL),
+                PATH,
+                SETTINGS,
+                method,
+                Some(&(NAMESPACE, KEY).to_variant()),
+                VariantTy::new("(v)").ok(),
+                DBusCallFlags::NONE,
+                TIMEOUT_MS,
+                Cancellable::NONE,
+            )
+            .map_err(|err| debug!(%err, method, "portal read failed"))
+            .ok()?;
+        reply.child_value(0).as_variant()
+    }
+
+    /// Calls `on_change` from the main loop every time the portal reports a new scheme.
+    pub fn watch(&self, on_change: impl Fn(ColorScheme) + 'static) -> PortalWatch {
+        let id = self.connection.signal_subscribe(
+            Some(PORTAL),
+            Some(SETTINGS),
+            Some("SettingChanged"),
+            Some(PATH),
+            Some(NAMESPACE),
+            DBusSignalFlags::NONE,
+            move |_, _, _, _, _, params| {
+                // (namespace, key, value)
+                if params.child_value(1).str() != Some(KEY) {
+                    return;
+                }
+                if let Some(value) = params.child_value(2).as_variant()
+                    && let Some(value) = value.get::<u32>()
+                {
+                    on_change(ColorScheme::from_portal(value));
+                }
+            },
+        );
+        PortalWatch {
+            connection: self.connection.clone(),
+            id: Some(id),
+        }
+    }
+}
+
+/// Stops watching the portal when dropped.
+pub struct PortalWatch {
+    connection: DBusConnection,
+    id: Option<SignalSubscriptionId>,
+}
+
+impl Drop for PortalWatch {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.connection.signal_unsubscribe(id);
+        }
+    }
+}
+
+fn gtk_settings() -> Option<gtk::Settings> {
+    // druid initialises GTK when it starts, tests may not
+    if !gtk::is_initialized_main_thread() {
+        return None;
+    }
+    gtk::Settings::default()
+}
+
+// GTK only knows about dark themes, so it never reports `NoPreference`
+fn gtk_color_scheme(settings: &gtk::Settings) -> ColorScheme {
+    let dark_theme = settings
+        .property::<Option<String>>(GTK_THEME_NAME)
+        .is_some_and(|name| name.to_lowercase().ends_with("dark"));
+    if settings.property::<bool>(GTK_PREFER_DARK) || dark_theme {
+        ColorScheme::Dark
+    } else {
+        ColorScheme::Light
+    }
+}
+
+fn gtk_scheme() -> ColorScheme {
+    gtk_settings()
+        .map(|settings| gtk_color_scheme(&settings))
+        .unwrap_or(ColorScheme::NoPreference)
+}
+
+pub(super) struct Watch {
+    // only held to be dropped, filled in on the main loop once the reading thread found
+    // a portal
+    #[allow(dead_code)]
+    portal: Arc<OnceLock<PortalWatch>>,
+    gtk: Option<(gtk::Settings, Vec<SignalHandlerId>)>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        if let Some((settings, handlers)) = &mut self.gtk {
+            for handler in handlers.drain(..) {
+                settings.disconnect(handler);
+            }
+        }
+    }
+}
+
+pub(super) fn color_scheme() -> ColorScheme {
+    Portal::session()
+        .and_then(|portal| portal.color_scheme())
+        .unwrap_or_else(gtk_scheme)
+}
+
+pub(super) fn watch(report: impl Fn(ColorScheme) + Clone + Send + 'static) -> Watch {
+    // the GTK settings only speak for the desktop until the portal answers
+    let portal_answered = Arc::new(AtomicBool::new(false));
+    let gtk = gtk_settings().map(|settings| {
+        let answered = portal_answered.clone();
+        let report = report.clone();
+        let notify = move |settings: &gtk::Settings, _: &gio::glib::ParamSpec| {
+            if !answered.load(Ordering::Relaxed) {
+                report(gtk_color_scheme(settings));
+            }
+        };
+        let handlers = vec![
+            settings.connect_notify_local(Some(GTK_THEME_NAME), notify.clone()),
+            settings.connect_notify_local(Some(GTK_PREFER_DARK), notify),
+        ];
+        (settings, handlers)
+    });
+
+    // connecting to the bus and reading can take the whole timeout, so neither happens
+    // on the main loop
+    let slot = Arc::new(OnceLock::new());
+    let weak_slot = Arc::downgrade(&slot);
+    thread::spawn(move || {
+        let portal = Portal::session();
+        let scheme = portal.as_ref().and_then(Portal::color_scheme);
+        // signals are delivered on the context the subscription was made from
+        MainContext::default().invoke(move || match (portal, scheme) {
+            (Some(portal), Some(scheme)) => {
+                // the widget went away while the portal was read
+                let Some(slot) = weak_slot.upgrade() else {
+                    return;
+                };
+                let answered = portal_answered.clone();
+                let on_change = report.clone();
+                let _ = slot.set(portal.watch(move |scheme| {
+                    answered.store(true, Ordering::Relaxed);
+                    on_change(scheme)
+                }));
+                portal_answered.store(true, Ordering::Relaxed);
+                report(scheme);
+            }
+            _ => report(gtk_scheme()),
+        });
+    });
+
+    Watch { portal: slot, gtk }
+}
@@ -0,0 +1,117 @@
+//! Follows the desktop's light/dark preference.
+//!
+//! With the `system-theme` feature on Linux the preference comes from the freedesktop
+//! settings portal (`org.freedesktop.appearance` `color-scheme`), falling back to the GTK
+//! theme settings when no portal answers. Everywhere else the desktop reports no preference.
+
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{Lens, Target};
+use tracing::debug;
+
+use super::Preset;
+
+#[cfg(all(feature = "system-theme", target_os = "linux"))]
+mod linux;
+#[cfg(all(feature = "system-theme", target_os = "linux"))]
+use linux as platform;
+#[cfg(all(feature = "system-theme", target_os = "linux"))]
+pub use linux::{Portal, PortalWatch};
+
+#[cfg(not(all(feature = "system-theme", target_os = "linux")))]
+mod fallback;
+#[cfg(not(all(feature = "system-theme", target_os = "linux")))]
+use fallback as platform;
+
+/// The color scheme the desktop asks applications to use.
+#[derive(Debug, Clone, Copy, Data, PartialEq, Eq)]
+pub enum ColorScheme {
+    NoPreference,
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    /// The matching preset, `fallback` when the desktop has no preference.
+    pub fn preset(self, fallback: Preset) -> Preset {
+        match self {
+            ColorScheme::NoPreference => fallback,
+            ColorScheme::Dark => Preset::Dark,
+            ColorScheme::Light => Preset::Light,
+        }
+    }
+}
+
+crate::selectors! {
+    /// Sent to a [`FollowSystem`] widget when the desktop switches color scheme
+    COLOR_SCHEME_CHANGED: ColorScheme,
+}
+
+/// The desktop's current preference.
+///
+/// Blocks until the portal answers, which can take a second when none is running.
+/// [`FollowSystem`] reads it off the main thread.
+pub fn color_scheme() -> ColorScheme {
+    platform::color_scheme()
+}
+
+/// Keeps the [`Preset`] behind a lens in line with the desktop preference.
+///
+/// ```ignore
+/// ThemeScope::new(|data: &AppState| data.theme, ui)
+///     .controller(FollowSystem::new(AppState::theme))
+/// ```
+pub struct FollowSystem<L> {
+    lens: L,
+    fallback: Preset,
+    watch: Option<platform::Watch>,
+}
+
+impl<L> FollowSystem<L> {
+    pub fn new(lens: L) -> Self {
+        FollowSystem {
+            lens,
+            fallback: Preset::Dark,
+            watch: None,
+        }
+    }
+
+    /// The preset used while the desktop has no preference, dark by default.
+    pub fn with_fallback(mut self, fallback: Preset) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    fn apply<T>(&self, data: &mut T, scheme: ColorScheme)
+    where
+        L: Lens<T, Preset>,
+    {
+        let preset = scheme.preset(self.fallback);
+        self.lens.with_mut(data, |current| *current = preset);
+    }
+}
+
+impl<T: Data, L: Lens<T, Preset>, W: Widget<T>> Controller<T, W> for FollowSystem<L> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        crate::match_command!(event, ctx => {
+            Command(COLOR_SCHEME_CHANGED(scheme)) => {
+                debug!(?scheme, "desktop color scheme changed");
+                self.apply(data, *scheme);
+            }
+            _ => {
+                if let Event::WindowConnected = event
+                    && self.watch.is_none()
+                {
+                    let sink = ctx.get_external_handle();
+                    let target = Target::Widget(ctx.widget_id());
+                    // the platform reports the current scheme once it has read it and every
+                    // change after that, the sink takes them to the widget
+                    self.watch = Some(platform::watch(move |scheme| {
+                        let _ = sink.submit_command(COLOR_SCHEME_CHANGED, scheme, target);
+                    }));
+                }
+            }
+        });
+        child.event(ctx, event, data, env)
+    }
+}
@@ -1,15 +1,68 @@
 use druid::widget::{Label, LabelText};
 use druid::{
-    Affine, BoxConstraints, Color, Data, Env, Event, EventCtx, Insets, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, RenderContext, Size, UpdateCtx, Widget, theme,
+    Affine, BoxConstraints, Color, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, RenderContext, Size, UpdateCtx, Widget,
 };
 use tracing::trace;
 
-const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
+use crate::animation::{Animated, Tween};
+use crate::theme;
 
 pub struct AdvancedButton<T> {
     label: Label<T>,
     label_size: Size,
+    border: Animated<Color>,
+    stroke: Animated<f64>,
+    fill: Animated<Color>,
+}
+
+/// What the button shows, read off the context it is in.
+struct Look {
+    border: Color,
+    stroke: f64,
+    fill: Color,
+}
+
+macro_rules! look {
+    ($ctx:expr, $env:expr) => {
+        Look::new(
+            $ctx.is_hot(),
+            $ctx.is_active() && !$ctx.is_disabled(),
+            $ctx.is_focused() || $ctx.has_focus(),
+            $ctx.is_disabled(),
+            $env,
+        )
+    };
+}
+
+impl Look {
+    fn new(hot: bool, active: bool, focused: bool, disabled: bool, env: &Env) -> Look {
+        let border = theme::get(env, theme::BORDER);
+        let accent = theme::get(env, theme::ACCENT);
+        let border = if focused || active {
+            accent
+        } else if hot && !disabled {
+            // hovering leans towards the accent without committing to it
+            border.tween(&accent, 0.5)
+        } else {
+            border
+        };
+        let stroke = if active {
+            theme::get(env, theme::ACTIVE_BORDER_WIDTH)
+        } else {
+            theme::get(env, theme::BORDER_WIDTH)
+        };
+        let fill = if disabled {
+            theme::get(env, theme::SURFACE_DISABLED)
+        } else {
+            theme::get(env, theme::SURFACE)
+        };
+        Look {
+            border,
+            stroke,
+            fill,
+        }
+    }
 }
 
 impl<T: Data> AdvancedButton<T> {
@@ -17,8 +70,32 @@ impl<T: Data> AdvancedButton<T> {
         AdvancedButton {
             label: Label::new(text),
             label_size: Size::ZERO,
+            border: Animated::new(Color::TRANSPARENT),
+            stroke: Animated::new(0.),
+            fill: Animated::new(Color::TRANSPARENT),
         }
     }
+
+    /// Starts easing towards `look`, returns whether there is anything to animate.
+    fn animate_to(&mut self, look: Look) -> bool {
+        // all three run, `|` doesn't short-circuit
+        self.border.animate_to(look.border)
+            | self.stroke.animate_to(look.stroke)
+            | self.fill.animate_to(look.fill)
+    }
+
+    /// Whether the running or finished animations end at `look`.
+    fn heads_to(&self, look: &Look) -> bool {
+        *self.border.target() == look.border
+            && *self.stroke.target() == look.stroke
+            && *self.fill.target() == look.fill
+    }
+
+    fn jump_to(&mut self, look: Look) {
+        self.border.jump_to(look.border);
+        self.stroke.jump_to(look.stroke);
+        self.fill.jump_to(look.fill);
+    }
 }
 
 // custom button
@@ -26,47 +103,78 @@ impl<T: Data> AdvancedButton<T> {
 // cool guide - https://www.pauljmiller.com/posts/druid-widget-tutorial.html
 
 impl<T: Data> Widget<T> for AdvancedButton<T> {
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
         match event {
             Event::MouseDown(_) => {
                 if !ctx.is_disabled() {
                     ctx.set_active(true);
-                    ctx.request_paint();
                     trace!("Button {:?} pressed", ctx.widget_id());
                 }
             }
             Event::MouseUp(_) => {
                 if ctx.is_active() && !ctx.is_disabled() {
-                    ctx.request_paint();
                     trace!("Button {:?} released", ctx.widget_id());
                 }
                 ctx.set_active(false);
             }
-            _ => (),
+            Event::AnimFrame(nanos) => {
+                let running = self.border.advance(*nanos)
+                    | self.stroke.advance(*nanos)
+                    | self.fill.advance(*nanos);
+                if running {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+                return;
+            }
+            _ => return,
         }
+        if self.animate_to(look!(ctx, env)) {
+            ctx.request_anim_frame();
+        }
+        ctx.request_paint();
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::WidgetAdded => self.jump_to(look!(ctx, env)),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::FocusChanged(_)
+            | LifeCycle::DisabledChanged(_) => {
+                if self.animate_to(look!(ctx, env)) {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
+            _ => {}
         }
         self.label.lifecycle(ctx, event, data, env)
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        // theme switches fade on their own, the button just follows, other keys changing
+        // leave a hover or press easing in alone
+        if ctx.env_changed() {
+            let look = look!(ctx, env);
+            if !self.heads_to(&look) {
+                self.jump_to(look);
+                ctx.request_paint();
+            }
+        }
         self.label.update(ctx, old_data, data, env);
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Button");
-        let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
+        let insets = theme::get(env, theme::CONTROL_INSETS);
+        let padding = Size::new(insets.x_value(), insets.y_value());
         let label_bc = bc.shrink(padding).loosen();
         self.label_size = self.label.layout(ctx, &label_bc, data, env);
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
-        let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let min_height = env.get(druid::theme::BORDERED_WIDGET_HEIGHT);
         let baseline = self.label.baseline_offset();
-        ctx.set_baseline_offset(baseline + LABEL_INSETS.y1);
+        ctx.set_baseline_offset(baseline + insets.y1);
 
         let button_size = bc.constrain(Size::new(
             self.label_size.width + padding.width,
@@ -77,56 +185,21 @@ impl<T: Data> Widget<T> for AdvancedButton<T> {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        // let is_active = ctx.is_active() && !ctx.is_disabled();
-        // let is_hot = ctx.is_hot();
         let size = ctx.size();
 
-        let stroke_width = if ctx.is_active() {
-            4.0
-        } else {
-            env.get(theme::BUTTON_BORDER_WIDTH)
-        };
-
-        // let stroke_width = ctx.is_active() ? 2.0 : env.get(theme::BUTTON_BORDER_WIDTH);
+        let stroke_width = self.stroke.value();
 
         let rounded_rect = size
             .to_rect()
             .inset(-stroke_width / 2.0)
-            .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
-
-        // let bg_gradient = if ctx.is_disabled() {
-        //     LinearGradient::new(
-        //         UnitPoint::TOP,
-        //         UnitPoint::BOTTOM,
-        //         (
-        //             env.get(theme::DISABLED_BUTTON_LIGHT),
-        //             env.get(theme::DISABLED_BUTTON_DARK),
-        //         ),
-        //     )
-        // } else if is_active {
-        //     LinearGradient::new(
-        //         UnitPoint::TOP,
-        //         UnitPoint::BOTTOM,
-        //         (env.get(theme::BUTTON_DARK), env.get(theme::BUTTON_LIGHT)),
-        //     )
-        // } else {
-        //     LinearGradient::new(
-        //         UnitPoint::TOP,
-        //         UnitPoint::BOTTOM,
-        //         (env.get(theme::BUTTON_LIGHT), env.get(theme::BUTTON_DARK)),
-        //     )
-        // };
-
-        let main_border_color = if ctx.is_focused() || ctx.has_focus() || ctx.is_active() {
-            // #3474f0
-            &Color::rgb8(52, 116, 240)
-        } else {
-            &Color::rgb8(81, 83, 85)
-        };
+            .to_rounded_rect(theme::get(env, theme::CORNER_RADIUS));
+
+        let main_border_color = self.border.value();
+        let fill = self.fill.value();
 
-        ctx.stroke(rounded_rect, main_border_color, stroke_width);
+        ctx.stroke(rounded_rect, &main_border_color, stroke_width);
 
-        ctx.fill(rounded_rect, &Color::rgb8(43, 45, 48));
+        ctx.fill(rounded_rect, &fill);
 
         ctx.with_save(|ctx| {
             let label_offset = (size.to_vec2() - self.label_size.to_vec2()) / 2.0;
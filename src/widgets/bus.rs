@@ -0,0 +1,139 @@
+//! Publish and subscribe between widgets that don't know each other's `WidgetId`.
+//!
+//! Channels are selectors declared with `selectors!`, which is also how they are named.
+//! There is no subscribing by the name string alone: druid keeps the name of a command
+//! private and only compares it through `Command::is` with a `Selector`, so a subscriber
+//! needs the selector to recognize its commands anyway.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{Command, Selector, Target};
+
+// a subscriber gets every published command whose selector matches, sent to its own
+// `WidgetId`
+type Filter = Rc<dyn Fn(&Command) -> bool>;
+
+struct Subscriber {
+    widget: WidgetId,
+    accepts: Filter,
+    alive: Weak<()>,
+}
+
+thread_local! {
+    // druid only touches widgets from the main thread
+    static SUBSCRIBERS: RefCell<Vec<Subscriber>> = const { RefCell::new(Vec::new()) };
+}
+
+fn filter<T: Any>(channel: Selector<T>) -> Filter {
+    Rc::new(move |cmd: &Command| cmd.is(channel))
+}
+
+/// Keeps a widget subscribed to bus channels for as long as it is alive.
+///
+/// Store it in the widget (or controller) and subscribe on `LifeCycle::WidgetAdded`,
+/// dropping the widget drops every subscription made through it.
+#[derive(Default)]
+pub struct Subscription {
+    alive: Rc<()>,
+}
+
+impl Subscription {
+    pub fn new() -> Subscription {
+        Subscription::default()
+    }
+
+    pub fn subscribe<T: Any>(&self, widget: WidgetId, channel: Selector<T>) {
+        self.register(widget, filter(channel));
+    }
+
+    fn register(&self, widget: WidgetId, accepts: Filter) {
+        SUBSCRIBERS.with(|subscribers| {
+            subscribers.borrow_mut().push(Subscriber {
+                widget,
+                accepts,
+                alive: Rc::downgrade(&self.alive),
+            })
+        });
+    }
+}
+
+/// Subscribes the wrapped widget to bus channels.
+///
+/// ```ignore
+/// Label::new("closed").controller(Subscribe::to(DROPDOWN_CLOSED))
+/// ```
+pub struct Subscribe {
+    channels: Vec<Filter>,
+    subscription: Subscription,
+}
+
+impl Subscribe {
+    pub fn to<T: Any>(channel: Selector<T>) -> Subscribe {
+        Subscribe {
+            channels: vec![filter(channel)],
+            subscription: Subscription::new(),
+        }
+    }
+
+    pub fn and<T: Any>(mut self, channel: Selector<T>) -> Subscribe {
+        self.channels.push(filter(channel));
+        self
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Subscribe {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            for channel in &self.channels {
+                self.subscription.register(ctx.widget_id(), channel.clone());
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
+
+/// Returns the live subscribers of `cmd`, forgetting the ones whose widget is gone.
+fn subscribers_of(cmd: &Command) -> Vec<WidgetId> {
+    SUBSCRIBERS.with(|subscribers| {
+        let mut subscribers = subscribers.borrow_mut();
+        subscribers.retain(|s| s.alive.strong_count() > 0);
+        subscribers
+            .iter()
+            .filter(|s| (s.accepts)(cmd))
+            .map(|s| s.widget)
+            .collect()
+    })
+}
+
+/// Sends a command to every widget subscribed to its selector.
+pub trait Publish {
+    fn publish(&mut self, cmd: impl Into<Command>);
+}
+
+macro_rules! impl_publish {
+    ($($ctx:ty),+) => {
+        $(
+            impl Publish for $ctx {
+                fn publish(&mut self, cmd: impl Into<Command>) {
+                    let cmd = cmd.into();
+                    for widget in subscribers_of(&cmd) {
+                        self.submit_command(cmd.clone().to(Target::Widget(widget)));
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_publish!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>);
@@ -0,0 +1,600 @@
+//! A color swatch opening a color editor in a [`Dropdown`].
+//!
+//! The editor has a saturation and value square for the current hue, hue and alpha
+//! sliders, hex and RGB fields, and the colors recently picked with any [`ColorPicker`]
+//! of the app.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use druid::kurbo::Circle;
+use druid::lens::Map;
+use druid::piet::{ColorParseError, LinearGradient};
+use druid::text::{Formatter, ParseFormatter, Selection, Validation, ValidationError};
+use druid::widget::prelude::*;
+use druid::widget::{CrossAxisAlignment, Flex, Label, TextBox};
+use druid::{Color, Lens, Point, Rect, UnitPoint, WidgetExt, WidgetPod};
+
+use crate::theme;
+use crate::widgets::dropdown::{DROPDOWN_SHOW, Dropdown};
+
+const SQUARE_WIDTH: f64 = 200.;
+const SQUARE_HEIGHT: f64 = 150.;
+const SLIDER_HEIGHT: f64 = 14.;
+const SWATCH: Size = Size::new(44., 24.);
+const RECENT_SWATCH: f64 = 18.;
+const MAX_RECENT: usize = 10;
+const CHECKER: f64 = 6.;
+
+thread_local! {
+    // shared by every picker of the app, newest first
+    static RECENT: RefCell<Vec<Color>> = const { RefCell::new(Vec::new()) };
+}
+
+fn remember(color: &Color) {
+    RECENT.with_borrow_mut(|recent| {
+        let rgba = color.as_rgba_u32();
+        recent.retain(|other| other.as_rgba_u32() != rgba);
+        recent.insert(0, *color);
+        recent.truncate(MAX_RECENT);
+    });
+}
+
+/// A color as hue, saturation, value and alpha, each but the hue from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+struct Hsva {
+    /// In degrees, from 0 to 360
+    hue: f64,
+    saturation: f64,
+    value: f64,
+    alpha: f64,
+}
+
+impl Hsva {
+    /// `color` in HSV, keeping `hue` for grays, which have none of their own.
+    fn from_color(color: &Color, hue: f64) -> Hsva {
+        let (r, g, b, alpha) = color.as_rgba();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0. {
+            hue
+        } else if max == r {
+            60. * ((g - b) / delta).rem_euclid(6.)
+        } else if max == g {
+            60. * ((b - r) / delta + 2.)
+        } else {
+            60. * ((r - g) / delta + 4.)
+        };
+        let saturation = if max == 0. { 0. } else { delta / max };
+        Hsva {
+            hue,
+            saturation,
+            value: max,
+            alpha,
+        }
+    }
+
+    fn to_color(self) -> Color {
+        let chroma = self.value * self.saturation;
+        let sector = (self.hue / 60.).rem_euclid(6.);
+        let x = chroma * (1. - (sector % 2. - 1.).abs());
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.),
+            1 => (x, chroma, 0.),
+            2 => (0., chroma, x),
+            3 => (0., x, chroma),
+            4 => (x, 0., chroma),
+            _ => (chroma, 0., x),
+        };
+        let m = self.value - chroma;
+        Color::rgba(r + m, g + m, b + m, self.alpha)
+    }
+
+    fn pure_hue(self) -> Color {
+        Hsva {
+            saturation: 1.,
+            value: 1.,
+            alpha: 1.,
+            ..self
+        }
+        .to_color()
+    }
+}
+
+fn paint_checkerboard(ctx: &mut PaintCtx, rect: Rect) {
+    ctx.with_save(|ctx| {
+        ctx.clip(rect);
+        ctx.fill(rect, &Color::WHITE);
+        let light_gray = Color::grey8(0xcc);
+        let columns = (rect.width() / CHECKER).ceil() as usize;
+        let rows = (rect.height() / CHECKER).ceil() as usize;
+        for row in 0..rows {
+            for column in (row % 2..columns).step_by(2) {
+                let origin = Point::new(
+                    rect.x0 + column as f64 * CHECKER,
+                    rect.y0 + row as f64 * CHECKER,
+                );
+                ctx.fill(
+                    Rect::from_origin_size(origin, (CHECKER, CHECKER)),
+                    &light_gray,
+                );
+            }
+        }
+    });
+}
+
+/// Picks saturation left to right and value bottom to top.
+struct SvSquare;
+
+impl SvSquare {
+    fn pick(ctx: &mut EventCtx, data: &mut Hsva, pos: Point) {
+        let size = ctx.size();
+        data.saturation = (pos.x / size.width).clamp(0., 1.);
+        data.value = 1. - (pos.y / size.height).clamp(0., 1.);
+    }
+}
+
+impl Widget<Hsva> for SvSquare {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Hsva, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.set_active(true);
+                SvSquare::pick(ctx, data, mouse.pos);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => SvSquare::pick(ctx, data, mouse.pos),
+            Event::MouseUp(_) => ctx.set_active(false),
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &Hsva, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Hsva, data: &Hsva, _env: &Env) {
+        if old_data != data {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &Hsva, _: &Env) -> Size {
+        bc.constrain(Size::new(SQUARE_WIDTH, SQUARE_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Hsva, _env: &Env) {
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect, &data.pure_hue());
+        let white = LinearGradient::new(
+            UnitPoint::LEFT,
+            UnitPoint::RIGHT,
+            (Color::WHITE, Color::WHITE.with_alpha(0.)),
+        );
+        ctx.fill(rect, &white);
+        let black = LinearGradient::new(
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+            (Color::BLACK.with_alpha(0.), Color::BLACK),
+        );
+        ctx.fill(rect, &black);
+
+        let marker = Point::new(
+            data.saturation * rect.width(),
+            (1. - data.value) * rect.height(),
+        );
+        ctx.stroke(Circle::new(marker, 5.), &Color::BLACK, 3.);
+        ctx.stroke(Circle::new(marker, 5.), &Color::WHITE, 1.5);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Channel {
+    Hue,
+    Alpha,
+}
+
+/// A horizontal slider over the hue or the alpha.
+struct ChannelSlider(Channel);
+
+impl ChannelSlider {
+    fn pick(&self, ctx: &mut EventCtx, data: &mut Hsva, x: f64) {
+        let fraction = (x / ctx.size().width).clamp(0., 1.);
+        match self.0 {
+            Channel::Hue => data.hue = fraction * 360.,
+            Channel::Alpha => data.alpha = fraction,
+        }
+    }
+}
+
+impl Widget<Hsva> for ChannelSlider {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Hsva, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.set_active(true);
+                self.pick(ctx, data, mouse.pos.x);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => self.pick(ctx, data, mouse.pos.x),
+            Event::MouseUp(_) => ctx.set_active(false),
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &Hsva, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Hsva, data: &Hsva, _env: &Env) {
+        if old_data != data {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &Hsva, _: &Env) -> Size {
+        bc.constrain(Size::new(SQUARE_WIDTH, SLIDER_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Hsva, env: &Env) {
+        let rect = ctx.size().to_rect();
+        let fraction = match self.0 {
+            Channel::Hue => {
+                let stops: Vec<Color> = (0..=6)
+                    .map(|sector| {
+                        Hsva {
+                            hue: sector as f64 * 60.,
+                            ..*data
+                        }
+                        .pure_hue()
+                    })
+                    .collect();
+                ctx.fill(
+                    rect,
+                    &LinearGradient::new(UnitPoint::LEFT, UnitPoint::RIGHT, stops.as_slice()),
+                );
+                data.hue / 360.
+            }
+            Channel::Alpha => {
+                paint_checkerboard(ctx, rect);
+                let opaque = Hsva { alpha: 1., ..*data }.to_color();
+                let gradient = LinearGradient::new(
+                    UnitPoint::LEFT,
+                    UnitPoint::RIGHT,
+                    (opaque.with_alpha(0.), opaque),
+                );
+                ctx.fill(rect, &gradient);
+                data.alpha
+            }
+        };
+        ctx.stroke(rect, &theme::get(env, theme::BORDER), 1.);
+
+        let x = fraction * rect.width();
+        let thumb = Rect::new(x - 2., 0., x + 2., rect.height());
+        ctx.fill(thumb, &Color::WHITE);
+        ctx.stroke(thumb, &Color::BLACK, 1.);
+    }
+}
+
+/// A color filling its size over a checkerboard, showing through where it is transparent.
+struct Swatch;
+
+impl Widget<Color> for Swatch {
+    fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut Color, _: &Env) {}
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &Color, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Color, data: &Color, _env: &Env) {
+        if old_data.as_rgba_u32() != data.as_rgba_u32() {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &Color, _: &Env) -> Size {
+        bc.constrain(SWATCH)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Color, env: &Env) {
+        let rect = ctx.size().to_rect();
+        paint_checkerboard(ctx, rect);
+        ctx.fill(rect, data);
+        ctx.stroke(rect.inset(-0.5), &theme::get(env, theme::BORDER), 1.);
+    }
+}
+
+/// Reads and writes `#rrggbb`, or `#rrggbbaa` for translucent colors, for a
+/// `TextBox<Color>`.
+pub struct HexFormatter {
+    alpha: bool,
+}
+
+impl HexFormatter {
+    pub fn new() -> Self {
+        HexFormatter { alpha: true }
+    }
+
+    /// A formatter for opaque colors, refusing the alpha digits.
+    pub fn without_alpha() -> Self {
+        HexFormatter { alpha: false }
+    }
+}
+
+impl Default for HexFormatter {
+    fn default() -> Self {
+        HexFormatter::new()
+    }
+}
+
+impl Formatter<Color> for HexFormatter {
+    fn format(&self, value: &Color) -> String {
+        let (r, g, b, a) = value.as_rgba8();
+        if a == 255 || !self.alpha {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        let digits = input.strip_prefix('#').unwrap_or(input);
+        let max = if self.alpha { 8 } else { 6 };
+        if let Some((idx, byte)) = digits
+            .bytes()
+            .enumerate()
+            .find(|(_, b)| !b.is_ascii_hexdigit())
+        {
+            Validation::failure(ColorParseError::NotHex { idx, byte })
+        } else if digits.len() > max {
+            Validation::failure(ColorParseError::WrongSize(digits.len()))
+        } else {
+            Validation::success()
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<Color, ValidationError> {
+        let input = input.trim();
+        let digits = input.strip_prefix('#').unwrap_or(input).len();
+        // `#rgba` and `#rrggbbaa` carry an alpha
+        if !self.alpha && (digits == 4 || digits == 8) {
+            return Err(ValidationError::new(ColorParseError::WrongSize(digits)));
+        }
+        Color::from_hex_str(input).map_err(ValidationError::new)
+    }
+}
+
+/// The red, green or blue channel of a color, from 0 to 255.
+fn channel(index: usize) -> impl Lens<Color, u8> {
+    Map::new(
+        move |color: &Color| {
+            let (r, g, b, _) = color.as_rgba8();
+            [r, g, b][index]
+        },
+        move |color: &mut Color, value: u8| {
+            let (r, g, b, a) = color.as_rgba8();
+            let mut rgb = [r, g, b];
+            rgb[index] = value;
+            *color = Color::rgba8(rgb[0], rgb[1], rgb[2], a);
+        },
+    )
+}
+
+fn fields(alpha: bool) -> impl Widget<Color> {
+    let mut row = Flex::row().with_flex_child(
+        TextBox::new()
+            .with_formatter(HexFormatter { alpha })
+            .update_data_while_editing(true),
+        1.,
+    );
+    for (index, name) in ["R", "G", "B"].into_iter().enumerate() {
+        row.add_default_spacer();
+        row.add_child(Label::new(name));
+        row.add_child(
+            TextBox::new()
+                .with_formatter(ParseFormatter::<u8>::new())
+                .update_data_while_editing(true)
+                .fix_width(40.)
+                .lens(channel(index)),
+        );
+    }
+    row.cross_axis_alignment(CrossAxisAlignment::Center)
+}
+
+fn recent_colors() -> impl Widget<Color> {
+    let mut row = Flex::row();
+    for color in RECENT.with_borrow(|recent| recent.clone()) {
+        row.add_child(
+            Swatch
+                .fix_size(RECENT_SWATCH, RECENT_SWATCH)
+                .on_click(move |_, data: &mut Color, _| *data = color)
+                .padding((0., 0., 4., 0.)),
+        );
+    }
+    row
+}
+
+/// The editor a [`ColorPicker`] opens, for use inline too.
+///
+/// The sliders keep their hue when they move to a gray, which has none of its own.
+pub struct ColorEditor {
+    hsva: Hsva,
+    controls: WidgetPod<Hsva, Box<dyn Widget<Hsva>>>,
+    fields: WidgetPod<Color, Box<dyn Widget<Color>>>,
+    /// Whether the color may be translucent, otherwise it is kept opaque
+    alpha: bool,
+    /// The color when the editor opened, which doesn't become a recent color
+    initial: Option<u32>,
+}
+
+impl ColorEditor {
+    pub fn new() -> Self {
+        ColorEditor::with_alpha(true)
+    }
+
+    /// An editor for colors that stay opaque, without the alpha slider.
+    pub fn without_alpha() -> Self {
+        ColorEditor::with_alpha(false)
+    }
+
+    fn with_alpha(alpha: bool) -> Self {
+        let mut controls = Flex::column()
+            .with_child(SvSquare)
+            .with_default_spacer()
+            .with_child(ChannelSlider(Channel::Hue));
+        if alpha {
+            controls.add_default_spacer();
+            controls.add_child(ChannelSlider(Channel::Alpha));
+        }
+        let controls = controls.cross_axis_alignment(CrossAxisAlignment::Start);
+        let fields = Flex::column()
+            .with_default_spacer()
+            .with_child(fields(alpha))
+            .with_default_spacer()
+            .with_child(recent_colors())
+            .cross_axis_alignment(CrossAxisAlignment::Start);
+        ColorEditor {
+            hsva: Hsva::from_color(&Color::BLACK, 0.),
+            controls: WidgetPod::new(controls.boxed()),
+            fields: WidgetPod::new(fields.boxed()),
+            alpha,
+            initial: None,
+        }
+    }
+
+    /// `color` as the editor keeps it, made opaque when it has no alpha.
+    fn kept(&self, color: &Color) -> Color {
+        if self.alpha {
+            *color
+        } else {
+            color.with_alpha(1.)
+        }
+    }
+}
+
+impl Default for ColorEditor {
+    fn default() -> Self {
+        ColorEditor::new()
+    }
+}
+
+impl Widget<Color> for ColorEditor {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Color, env: &Env) {
+        let kept = self.kept(data);
+        if kept.as_rgba_u32() != data.as_rgba_u32() {
+            *data = kept;
+        }
+        if let Event::WindowDisconnected = event
+            && self.initial != Some(data.as_rgba_u32())
+        {
+            remember(data);
+        }
+        let mut hsva = self.hsva;
+        self.controls.event(ctx, event, &mut hsva, env);
+        if hsva != self.hsva {
+            self.hsva = hsva;
+            *data = hsva.to_color();
+        }
+        self.fields.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Color, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let color = self.kept(data);
+            self.hsva = Hsva::from_color(&color, 0.);
+            self.initial = Some(color.as_rgba_u32());
+        }
+        self.controls.lifecycle(ctx, event, &self.hsva, env);
+        self.fields.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Color, data: &Color, env: &Env) {
+        // colors typed in the fields move the sliders, grays keep the hue they had
+        let color = self.kept(data);
+        if color.as_rgba_u32() != self.hsva.to_color().as_rgba_u32() {
+            self.hsva = Hsva::from_color(&color, self.hsva.hue);
+        }
+        self.controls.update(ctx, &self.hsva, env);
+        self.fields.update(ctx, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Color,
+        env: &Env,
+    ) -> Size {
+        let controls = self.controls.layout(ctx, &bc.loosen(), &self.hsva, env);
+        self.controls.set_origin(ctx, Point::ORIGIN);
+        let fields_bc = BoxConstraints::new(
+            Size::new(controls.width, 0.),
+            Size::new(controls.width, f64::INFINITY),
+        );
+        let fields = self.fields.layout(ctx, &fields_bc, data, env);
+        self.fields.set_origin(ctx, Point::new(0., controls.height));
+        bc.constrain(Size::new(controls.width, controls.height + fields.height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Color, env: &Env) {
+        self.controls.paint(ctx, &self.hsva, env);
+        self.fields.paint(ctx, data, env);
+    }
+}
+
+/// A swatch of the color, opening an editor for it.
+pub struct ColorPicker {
+    inner: WidgetPod<Color, Box<dyn Widget<Color>>>,
+    /// Shared with the popup, which reads it when it opens
+    alpha: Rc<Cell<bool>>,
+}
+
+impl ColorPicker {
+    pub fn new() -> Self {
+        let alpha = Rc::new(Cell::new(true));
+        let header =
+            Swatch.on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(DROPDOWN_SHOW));
+        let popup_alpha = alpha.clone();
+        let dropdown = Dropdown::new(header, move |_, _| {
+            ColorEditor::with_alpha(popup_alpha.get())
+        });
+        ColorPicker {
+            inner: WidgetPod::new(dropdown.boxed()),
+            alpha,
+        }
+    }
+
+    /// Keeps the color opaque, hiding the alpha slider and refusing alpha digits in the
+    /// hex field.
+    pub fn without_alpha(self) -> Self {
+        self.alpha.set(false);
+        self
+    }
+}
+
+impl Default for ColorPicker {
+    fn default() -> Self {
+        ColorPicker::new()
+    }
+}
+
+impl Widget<Color> for ColorPicker {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Color, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Color, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Color, data: &Color, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Color,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Color, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+}
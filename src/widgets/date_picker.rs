@@ -0,0 +1,601 @@
+//! A date picker opening a month calendar in a [`Dropdown`].
+//!
+//! [`DatePicker`] binds to the crate's own [`Date`], or to a [`DateTime`] when built with
+//! [`DatePicker::date_time`], which adds an hour and minute selector under the calendar.
+//! Dates are plain proleptic Gregorian days, with no time zone attached.
+//!
+//! In the calendar the arrow keys move between days, PageUp and PageDown between months,
+//! with Shift between years, and Enter picks the highlighted day.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use druid::piet::{Text, TextLayout, TextLayoutBuilder};
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label};
+use druid::{KbKey, Lens, Point, Rect, WidgetExt, WidgetPod};
+use tracing::warn;
+
+use crate::theme;
+use crate::widgets::advanced_button::AdvancedButton;
+use crate::widgets::dropdown::{DROPDOWN_SHOW, Dropdown};
+use crate::widgets::popup::POPUP_DISMISS;
+
+const CELL: f64 = 28.;
+const NAV_HEIGHT: f64 = 28.;
+const WEEKDAYS_HEIGHT: f64 = 20.;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+crate::selectors! {
+    /// Sent by a calendar in a popup to itself, to take the keyboard once it is added
+    pub(crate) TAKE_FOCUS,
+}
+
+/// A day of the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// `None` unless `month` is 1 to 12 and the month has a `day`.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Date> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(Date { year, month, day })
+    }
+
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    pub fn month(self) -> u8 {
+        self.month
+    }
+
+    pub fn day(self) -> u8 {
+        self.day
+    }
+
+    /// Days since Monday, so Monday is 0 and Sunday is 6.
+    pub fn weekday(self) -> u8 {
+        // 1970-01-01 was a Thursday
+        (self.days() + 3).rem_euclid(7) as u8
+    }
+
+    pub fn add_days(self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    /// Moves by whole months, the day shrinks to fit shorter months.
+    pub fn add_months(self, months: i32) -> Date {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u8 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Date { year, month, day }
+    }
+
+    pub fn first_of_month(self) -> Date {
+        Date { day: 1, ..self }
+    }
+
+    pub fn last_of_month(self) -> Date {
+        Date {
+            day: days_in_month(self.year, self.month),
+            ..self
+        }
+    }
+
+    /// Days since 1970-01-01.
+    fn days(self) -> i64 {
+        // Howard Hinnant's `days_from_civil`
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    fn from_days(days: i64) -> Date {
+        // and its inverse, `civil_from_days`
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u8;
+        let year = (year_of_era + era * 400 + (month <= 2) as i64) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A time of day to the minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+}
+
+impl Time {
+    pub const MIDNIGHT: Time = Time { hour: 0, minute: 0 };
+
+    pub fn new(hour: u8, minute: u8) -> Option<Time> {
+        (hour < 24 && minute < 60).then_some(Time { hour, minute })
+    }
+
+    pub fn hour(self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(self) -> u8 {
+        self.minute
+    }
+
+    fn add_hours(self, hours: i32) -> Time {
+        let hour = (self.hour as i32 + hours).rem_euclid(24) as u8;
+        Time { hour, ..self }
+    }
+
+    fn add_minutes(self, minutes: i32) -> Time {
+        let minute = (self.minute as i32 + minutes).rem_euclid(60) as u8;
+        Time { minute, ..self }
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data, Lens)]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+/// What a [`DatePicker`] and a [`Calendar`] can bind to, [`Date`] or [`DateTime`].
+pub trait PickerValue: Data + fmt::Display {
+    fn date(&self) -> Date;
+    fn set_date(&mut self, date: Date);
+}
+
+impl PickerValue for Date {
+    fn date(&self) -> Date {
+        *self
+    }
+
+    fn set_date(&mut self, date: Date) {
+        *self = date;
+    }
+}
+
+impl PickerValue for DateTime {
+    fn date(&self) -> Date {
+        self.date
+    }
+
+    fn set_date(&mut self, date: Date) {
+        self.date = date;
+    }
+}
+
+/// The dates a calendar lets the user pick.
+#[derive(Clone, Default)]
+struct Bounds {
+    min: Option<Date>,
+    max: Option<Date>,
+    disabled: Option<Rc<dyn Fn(Date) -> bool>>,
+}
+
+impl Bounds {
+    fn allows(&self, date: Date) -> bool {
+        self.min.is_none_or(|min| date >= min)
+            && self.max.is_none_or(|max| date <= max)
+            && !self
+                .disabled
+                .as_ref()
+                .is_some_and(|disabled| disabled(date))
+    }
+}
+
+/// A month of days to pick from, with buttons moving between months and years.
+pub struct Calendar<T> {
+    bounds: Bounds,
+    /// Set inside a [`DatePicker`], whose popup closes once a day is picked
+    dismiss_on_pick: bool,
+    /// The first day of the month shown
+    month: Date,
+    /// The day the keyboard moves, picked with Enter
+    cursor: Date,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: PickerValue> Calendar<T> {
+    pub fn new() -> Self {
+        Calendar::with_bounds(Bounds::default(), false)
+    }
+
+    fn with_bounds(bounds: Bounds, dismiss_on_pick: bool) -> Self {
+        let epoch = Date::from_days(0);
+        Calendar {
+            bounds,
+            dismiss_on_pick,
+            month: epoch,
+            cursor: epoch,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// The earliest date that can be picked.
+    pub fn with_min(mut self, min: Date) -> Self {
+        self.bounds.min = Some(min);
+        self
+    }
+
+    /// The latest date that can be picked.
+    pub fn with_max(mut self, max: Date) -> Self {
+        self.bounds.max = Some(max);
+        self
+    }
+
+    /// Dates for which `disabled` returns true can't be picked.
+    pub fn with_disabled(mut self, disabled: impl Fn(Date) -> bool + 'static) -> Self {
+        self.bounds.disabled = Some(Rc::new(disabled));
+        self
+    }
+
+    /// The first day in the grid, the Monday on or before the first of the month.
+    fn grid_start(&self) -> Date {
+        self.month.add_days(-(self.month.weekday() as i64))
+    }
+
+    fn cell_rect(index: usize) -> Rect {
+        let x = (index % 7) as f64 * CELL;
+        let y = NAV_HEIGHT + WEEKDAYS_HEIGHT + (index / 7) as f64 * CELL;
+        Rect::new(x, y, x + CELL, y + CELL)
+    }
+
+    fn move_cursor(&mut self, ctx: &mut EventCtx, cursor: Date) {
+        self.cursor = cursor;
+        self.month = cursor.first_of_month();
+        ctx.request_paint();
+    }
+
+    fn show_month(&mut self, ctx: &mut EventCtx, months: i32) {
+        self.month = self.month.add_months(months);
+        self.cursor = self.cursor.add_months(months);
+        ctx.request_paint();
+    }
+
+    fn pick(&mut self, ctx: &mut EventCtx, data: &mut T, date: Date) {
+        self.move_cursor(ctx, date);
+        if !self.bounds.allows(date) {
+            return;
+        }
+        data.set_date(date);
+        if self.dismiss_on_pick {
+            ctx.submit_command(POPUP_DISMISS.to(ctx.window_id()));
+        }
+    }
+}
+
+impl<T: PickerValue> Default for Calendar<T> {
+    fn default() -> Self {
+        Calendar::new()
+    }
+}
+
+impl<T: PickerValue> Widget<T> for Calendar<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, _env: &Env) {
+        crate::match_command!(event, ctx => {
+            Command(TAKE_FOCUS) => ctx.request_focus(),
+            _ => {}
+        });
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                if mouse.pos.y < NAV_HEIGHT {
+                    match (mouse.pos.x / CELL) as usize {
+                        0 => self.show_month(ctx, -12),
+                        1 => self.show_month(ctx, -1),
+                        5 => self.show_month(ctx, 1),
+                        6 => self.show_month(ctx, 12),
+                        _ => {}
+                    }
+                } else if let Some(index) =
+                    (0..42).find(|&index| Calendar::<T>::cell_rect(index).contains(mouse.pos))
+                {
+                    let date = self.grid_start().add_days(index as i64);
+                    self.pick(ctx, data, date);
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) => {
+                let cursor = self.cursor;
+                let years = if key.mods.shift() { 12 } else { 1 };
+                match &key.key {
+                    KbKey::ArrowLeft => self.move_cursor(ctx, cursor.add_days(-1)),
+                    KbKey::ArrowRight => self.move_cursor(ctx, cursor.add_days(1)),
+                    KbKey::ArrowUp => self.move_cursor(ctx, cursor.add_days(-7)),
+                    KbKey::ArrowDown => self.move_cursor(ctx, cursor.add_days(7)),
+                    KbKey::PageUp => self.move_cursor(ctx, cursor.add_months(-years)),
+                    KbKey::PageDown => self.move_cursor(ctx, cursor.add_months(years)),
+                    KbKey::Home => self.move_cursor(ctx, cursor.first_of_month()),
+                    KbKey::End => self.move_cursor(ctx, cursor.last_of_month()),
+                    KbKey::Enter => self.pick(ctx, data, cursor),
+                    KbKey::Character(c) if c == " " => self.pick(ctx, data, cursor),
+                    KbKey::Escape if self.dismiss_on_pick => {
+                        ctx.submit_command(POPUP_DISMISS.to(ctx.window_id()));
+                    }
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.cursor = data.date();
+                self.month = self.cursor.first_of_month();
+                if self.dismiss_on_pick {
+                    ctx.submit_command(TAKE_FOCUS.to(ctx.widget_id()));
+                }
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if old_data.date() != data.date() {
+            self.cursor = data.date();
+            self.month = self.cursor.first_of_month();
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.constrain(Size::new(
+            7. * CELL,
+            NAV_HEIGHT + WEEKDAYS_HEIGHT + 6. * CELL,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let font = theme::get(env, theme::FONT);
+        let text = theme::get(env, theme::TEXT);
+        let dimmed = theme::get(env, theme::TEXT_DISABLED);
+        let accent = theme::get(env, theme::ACCENT);
+        let selected = data.date();
+
+        let draw = |ctx: &mut PaintCtx, label: &str, rect: Rect, color: &druid::Color| {
+            let layout = ctx
+                .text()
+                .new_text_layout(label.to_owned())
+                .font(font.family.clone(), font.size)
+                .text_color(*color)
+                .build();
+            let Ok(layout) = layout.inspect_err(|err| warn!(%err, label, "calendar label layout"))
+            else {
+                return;
+            };
+            let size = layout.size();
+            let origin = rect.center() - (size.width / 2., size.height / 2.);
+            ctx.draw_text(&layout, origin);
+        };
+
+        let nav = |column: f64| Rect::new(column * CELL, 0., (column + 1.) * CELL, NAV_HEIGHT);
+        draw(ctx, "«", nav(0.), &text);
+        draw(ctx, "‹", nav(1.), &text);
+        draw(ctx, "›", nav(5.), &text);
+        draw(ctx, "»", nav(6.), &text);
+        let title = format!(
+            "{} {}",
+            MONTHS[self.month.month() as usize - 1],
+            self.month.year()
+        );
+        draw(
+            ctx,
+            &title,
+            Rect::new(2. * CELL, 0., 5. * CELL, NAV_HEIGHT),
+            &text,
+        );
+
+        for (column, weekday) in WEEKDAYS.iter().enumerate() {
+            let x = column as f64 * CELL;
+            let rect = Rect::new(x, NAV_HEIGHT, x + CELL, NAV_HEIGHT + WEEKDAYS_HEIGHT);
+            draw(ctx, weekday, rect, &dimmed);
+        }
+
+        let start = self.grid_start();
+        for index in 0..42 {
+            let date = start.add_days(index as i64);
+            let rect = Calendar::<T>::cell_rect(index);
+            let day = rect
+                .inset(-2.)
+                .to_rounded_rect(theme::get(env, theme::CORNER_RADIUS));
+            if date == selected {
+                ctx.fill(day, &accent);
+            }
+            if date == self.cursor && ctx.has_focus() {
+                ctx.stroke(day, &accent, 1.5);
+            }
+            let in_month = date.month() == self.month.month();
+            let color = if in_month && self.bounds.allows(date) {
+                &text
+            } else {
+                &dimmed
+            };
+            draw(ctx, &date.day().to_string(), rect, color);
+        }
+    }
+}
+
+fn time_selector() -> impl Widget<Time> {
+    let field = |value: fn(Time) -> u8, step: fn(Time, i32) -> Time| {
+        Flex::column()
+            .with_child(Button::new("▲").on_click(move |_, time: &mut Time, _| {
+                *time = step(*time, 1);
+            }))
+            .with_child(Label::new(move |time: &Time, _: &Env| {
+                format!("{:02}", value(*time))
+            }))
+            .with_child(Button::new("▼").on_click(move |_, time: &mut Time, _| {
+                *time = step(*time, -1);
+            }))
+    };
+    Flex::row()
+        .with_child(field(Time::hour, Time::add_hours))
+        .with_child(Label::new(":"))
+        .with_child(field(Time::minute, Time::add_minutes))
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+}
+
+/// A button showing the date, opening a [`Calendar`] to pick another one.
+pub struct DatePicker<T> {
+    inner: WidgetPod<T, Box<dyn Widget<T>>>,
+    /// Shared with the popup, which builds its calendar from them when it opens
+    bounds: Rc<RefCell<Bounds>>,
+}
+
+impl DatePicker<Date> {
+    pub fn new() -> Self {
+        DatePicker::build(|bounds| Calendar::with_bounds(bounds, true).boxed())
+    }
+}
+
+impl Default for DatePicker<Date> {
+    fn default() -> Self {
+        DatePicker::new()
+    }
+}
+
+impl DatePicker<DateTime> {
+    /// A picker with an hour and minute selector under the calendar, which stays open
+    /// until a click outside it.
+    pub fn date_time() -> Self {
+        DatePicker::build(|bounds| {
+            Flex::column()
+                .with_child(Calendar::with_bounds(bounds, false))
+                .with_default_spacer()
+                .with_child(time_selector().lens(DateTime::time))
+                .boxed()
+        })
+    }
+}
+
+impl<T: PickerValue> DatePicker<T> {
+    fn build(make_popup: impl Fn(Bounds) -> Box<dyn Widget<T>> + 'static) -> Self {
+        let bounds = Rc::new(RefCell::new(Bounds::default()));
+        let header = AdvancedButton::new(|value: &T, _: &Env| value.to_string())
+            .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(DROPDOWN_SHOW));
+        let popup_bounds = bounds.clone();
+        let dropdown = Dropdown::new(header, move |_, _| {
+            make_popup(popup_bounds.borrow().clone())
+        });
+        DatePicker {
+            inner: WidgetPod::new(dropdown.boxed()),
+            bounds,
+        }
+    }
+
+    /// The earliest date that can be picked.
+    pub fn with_min(self, min: Date) -> Self {
+        self.bounds.borrow_mut().min = Some(min);
+        self
+    }
+
+    /// The latest date that can be picked.
+    pub fn with_max(self, max: Date) -> Self {
+        self.bounds.borrow_mut().max = Some(max);
+        self
+    }
+
+    /// Dates for which `disabled` returns true can't be picked.
+    pub fn with_disabled(self, disabled: impl Fn(Date) -> bool + 'static) -> Self {
+        self.bounds.borrow_mut().disabled = Some(Rc::new(disabled));
+        self
+    }
+}
+
+impl<T: PickerValue> Widget<T> for DatePicker<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+}
@@ -0,0 +1,367 @@
+//! Sections whose body folds away under a clickable header.
+//!
+//! A [`Disclosure`] keeps whether it is open to itself, unless bound to a `bool` in the
+//! app data with [`Disclosure::bind_open`]. An [`Accordion`] stacks disclosures and keeps
+//! the indices of its open sections in an `OrdSet<usize>`, with at most one open when
+//! built with [`Accordion::single`].
+//!
+//! The header takes focus when clicked, and Space or Enter fold and unfold the body while
+//! it has focus.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use druid::im::OrdSet;
+use druid::kurbo::BezPath;
+use druid::widget::prelude::*;
+use druid::widget::{CrossAxisAlignment, Flex, Label, LabelText};
+use druid::{Affine, KbKey, Lens, LensExt, Point, Rect, WidgetExt, WidgetPod};
+
+use crate::animation::Animated;
+use crate::theme;
+
+const HEADER_HEIGHT: f64 = 28.;
+/// Width of the column the chevron turns in, left of the title
+const CHEVRON_COLUMN: f64 = 24.;
+const CHEVRON_SIZE: f64 = 4.;
+
+type IsOpenFn<T> = Box<dyn Fn(&T) -> bool>;
+type SetOpenFn<T> = Box<dyn Fn(&mut T, bool)>;
+type SectionsFn<T> = Rc<dyn Fn(&T) -> OrdSet<usize>>;
+type SetSectionsFn<T> = Rc<dyn Fn(&mut T, OrdSet<usize>)>;
+
+pub struct Disclosure<T> {
+    title: Label<T>,
+    title_size: Size,
+    body: WidgetPod<T, Box<dyn Widget<T>>>,
+    is_open: IsOpenFn<T>,
+    set_open: SetOpenFn<T>,
+    /// Whether the section is open, unless it is bound to the app data
+    own_state: Option<Rc<Cell<bool>>>,
+    /// Whether the body shows, as of the last event or update
+    open: bool,
+    /// 0 closed, 1 open, turning the chevron and revealing the body
+    reveal: Animated<f64>,
+    header_hot: bool,
+}
+
+impl<T: Data> Disclosure<T> {
+    /// A closed section keeping whether it is open to itself.
+    pub fn new(title: impl Into<LabelText<T>>, body: impl Widget<T> + 'static) -> Self {
+        let open = Rc::new(Cell::new(false));
+        let (get, set) = (open.clone(), open.clone());
+        Disclosure {
+            own_state: Some(open),
+            ..Disclosure::build(
+                title,
+                body,
+                move |_| get.get(),
+                move |_, value| set.set(value),
+            )
+        }
+    }
+
+    fn build(
+        title: impl Into<LabelText<T>>,
+        body: impl Widget<T> + 'static,
+        is_open: impl Fn(&T) -> bool + 'static,
+        set_open: impl Fn(&mut T, bool) + 'static,
+    ) -> Self {
+        Disclosure {
+            title: Label::new(title),
+            title_size: Size::ZERO,
+            body: WidgetPod::new(body.boxed()),
+            is_open: Box::new(is_open),
+            set_open: Box::new(set_open),
+            own_state: None,
+            open: false,
+            reveal: Animated::new(0.),
+            header_hot: false,
+        }
+    }
+
+    /// Builder-style method to start open, for sections keeping their own state.
+    ///
+    /// Sections bound with [`Disclosure::bind_open`] start the way the data says, before or
+    /// after this is called.
+    pub fn expanded(self) -> Self {
+        if let Some(open) = &self.own_state {
+            open.set(true);
+        }
+        self
+    }
+
+    /// Builder-style method to keep whether the section is open in the app data.
+    pub fn bind_open(mut self, lens: impl Lens<T, bool> + 'static) -> Self {
+        let lens = Rc::new(lens);
+        let set = lens.clone();
+        self.is_open = Box::new(move |data| lens.get(data));
+        self.set_open = Box::new(move |data, open| set.put(data, open));
+        self.own_state = None;
+        self
+    }
+
+    fn toggle(&mut self, ctx: &mut EventCtx, data: &mut T) {
+        (self.set_open)(data, !self.open);
+        // sections keeping their own state don't change the data, so no update follows
+        self.show(ctx, (self.is_open)(data));
+    }
+
+    /// Starts folding or unfolding towards `open`, if it isn't there already.
+    fn show(&mut self, ctx: &mut EventCtx, open: bool) {
+        if open == self.open {
+            return;
+        }
+        self.open = open;
+        if self.reveal.animate_to(if open { 1. } else { 0. }) {
+            ctx.request_anim_frame();
+        }
+        ctx.request_layout();
+    }
+
+    fn header_height(&self) -> f64 {
+        self.title_size.height.max(HEADER_HEIGHT)
+    }
+}
+
+impl<T: Data> Widget<T> for Disclosure<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let header = self.header_height();
+        match event {
+            Event::MouseMove(mouse) => {
+                let hot = ctx.is_hot() && mouse.pos.y < header;
+                if hot != self.header_hot {
+                    self.header_hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.pos.y < header => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                if ctx.is_hot() && mouse.pos.y < header {
+                    self.toggle(ctx, data);
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                match &key.key {
+                    KbKey::Enter => self.toggle(ctx, data),
+                    KbKey::Character(c) if c == " " => self.toggle(ctx, data),
+                    _ => return,
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::AnimFrame(nanos) => {
+                if self.reveal.advance(*nanos) {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+            }
+            _ => {}
+        }
+        // a folded body takes no input
+        if self.open || self.reveal.is_running() {
+            self.body.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.open = (self.is_open)(data);
+                self.reveal.jump_to(if self.open { 1. } else { 0. });
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            LifeCycle::HotChanged(false) => {
+                self.header_hot = false;
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+        self.title.lifecycle(ctx, event, data, env);
+        self.body.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let open = (self.is_open)(data);
+        if open != self.open {
+            self.open = open;
+            if self.reveal.animate_to(if open { 1. } else { 0. }) {
+                ctx.request_anim_frame();
+            }
+            ctx.request_layout();
+        }
+        self.title.update(ctx, old_data, data, env);
+        self.body.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Disclosure");
+        self.title_size = self.title.layout(ctx, &bc.loosen(), data, env);
+        let header = self.header_height();
+
+        let reveal = self.reveal.value();
+        // the body spans the width given, or takes what it needs when none is
+        let max_width = bc.max().width;
+        let body_bc = if max_width.is_finite() {
+            BoxConstraints::new(
+                Size::new(max_width, 0.),
+                Size::new(max_width, f64::INFINITY),
+            )
+        } else {
+            BoxConstraints::UNBOUNDED
+        };
+        let body = self.body.layout(ctx, &body_bc, data, env);
+        self.body.set_origin(ctx, Point::new(0., header));
+        let width = body.width.max(CHEVRON_COLUMN + self.title_size.width);
+
+        let title_top = (header - self.title_size.height) / 2.;
+        ctx.set_baseline_offset(self.title.baseline_offset() + title_top);
+        bc.constrain(Size::new(width, header + reveal * body.height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        let header = Rect::new(0., 0., size.width, self.header_height());
+        let radius = theme::get(env, theme::CORNER_RADIUS);
+        if self.header_hot || ctx.is_active() {
+            ctx.fill(
+                header.to_rounded_rect(radius),
+                &theme::get(env, theme::SURFACE),
+            );
+        }
+        if ctx.is_focused() {
+            ctx.stroke(
+                header.inset(-0.5).to_rounded_rect(radius),
+                &theme::get(env, theme::ACCENT),
+                1.,
+            );
+        }
+
+        // a chevron pointing right, turning to point down as the body opens
+        let reveal = self.reveal.value();
+        let mut chevron = BezPath::new();
+        chevron.move_to((-CHEVRON_SIZE / 2., -CHEVRON_SIZE));
+        chevron.line_to((CHEVRON_SIZE / 2., 0.));
+        chevron.line_to((-CHEVRON_SIZE / 2., CHEVRON_SIZE));
+        let center = Point::new(CHEVRON_COLUMN / 2., header.center().y);
+        let turn = Affine::translate(center.to_vec2())
+            * Affine::rotate(reveal * std::f64::consts::FRAC_PI_2);
+        let color = if ctx.is_disabled() {
+            theme::get(env, theme::TEXT_DISABLED)
+        } else {
+            theme::get(env, theme::TEXT)
+        };
+        ctx.stroke(turn * chevron, &color, 1.5);
+
+        ctx.with_save(|ctx| {
+            let top = (header.height() - self.title_size.height) / 2.;
+            ctx.transform(Affine::translate((CHEVRON_COLUMN, top)));
+            self.title.paint(ctx, data, env);
+        });
+
+        if reveal > 0. {
+            ctx.with_save(|ctx| {
+                ctx.clip(Rect::new(0., header.y1, size.width, size.height));
+                self.body.paint(ctx, data, env);
+            });
+        }
+    }
+}
+
+/// A column of [`Disclosure`]s, the indices of the open ones kept in the app data.
+pub struct Accordion<T> {
+    column: Flex<T>,
+    sections: usize,
+    single: bool,
+    open: SectionsFn<T>,
+    set_open: SetSectionsFn<T>,
+}
+
+impl<T: Data> Accordion<T> {
+    /// An accordion where opening a section closes the one that was open.
+    pub fn single(open: impl Lens<T, OrdSet<usize>> + 'static) -> Self {
+        Accordion::build(open, true)
+    }
+
+    /// An accordion opening and closing each section on its own.
+    pub fn multiple(open: impl Lens<T, OrdSet<usize>> + 'static) -> Self {
+        Accordion::build(open, false)
+    }
+
+    fn build(lens: impl Lens<T, OrdSet<usize>> + 'static, single: bool) -> Self {
+        let lens = Rc::new(lens);
+        let set = lens.clone();
+        Accordion {
+            column: Flex::column().cross_axis_alignment(CrossAxisAlignment::Fill),
+            sections: 0,
+            single,
+            open: Rc::new(move |data| lens.get(data)),
+            set_open: Rc::new(move |data, open| set.put(data, open)),
+        }
+    }
+
+    /// Builder-style method to add a section at the bottom.
+    pub fn with_section(
+        mut self,
+        title: impl Into<LabelText<T>>,
+        body: impl Widget<T> + 'static,
+    ) -> Self {
+        let index = self.sections;
+        self.sections += 1;
+        let single = self.single;
+        let (open, set_open) = (self.open.clone(), self.set_open.clone());
+        let is_open = move |data: &T| {
+            let open = open(data);
+            if single {
+                // from data that has several open, only the first shows
+                open.get_min() == Some(&index)
+            } else {
+                open.contains(&index)
+            }
+        };
+        let open = self.open.clone();
+        let set = move |data: &mut T, value: bool| {
+            let mut sections = if single { OrdSet::new() } else { open(data) };
+            if value {
+                sections.insert(index);
+            } else {
+                sections.remove(&index);
+            }
+            set_open(data, sections);
+        };
+        self.column
+            .add_child(Disclosure::build(title, body, is_open, set));
+        self
+    }
+}
+
+impl<T: Data> Widget<T> for Accordion<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.column.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.column.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.column.update(ctx, old_data, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.column.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.column.paint(ctx, data, env);
+    }
+}
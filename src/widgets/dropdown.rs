@@ -1,158 +1,122 @@
 use druid::Target;
-use druid::WindowSizePolicy;
-use druid::commands::CLOSE_WINDOW;
+use druid::WindowId;
 use druid::widget::Controller;
 use druid::widget::WidgetExt;
 use druid::widget::prelude::*;
-use druid::{Point, WindowConfig};
-use druid::{WindowId, WindowLevel};
+
+use tracing::{Span, debug, debug_span};
+
+use crate::theme;
+use crate::widgets::popup::{Popup, PopupTransition};
 
 type DropFn<T> = Box<dyn Fn(&T, &Env) -> Box<dyn Widget<T>>>;
 
 pub struct Dropdown<T> {
     drop: DropFn<T>,
-    window: Option<WindowId>,
+    popup: Popup,
 }
 
 crate::selectors! {
     DROPDOWN_SHOW,
     DROPDOWN_HIDE,
-    DROPDOWN_CLOSED,
+    /// Published on the bus when the popup window with this id goes away
+    DROPDOWN_CLOSED: WindowId,
 }
 
 impl<T: Data> Dropdown<T> {
+    // the header comes back wrapped, there is no `Self` to hand out
+    #[allow(clippy::new_ret_no_self)]
     pub fn new<W: 'static + Widget<T>, DW: Widget<T> + 'static>(
         header: W,
         make_drop: impl Fn(&T, &Env) -> DW + 'static,
+    ) -> impl Widget<T> {
+        Dropdown::with_transition(header, make_drop, PopupTransition::default())
+    }
+
+    /// Like [`Dropdown::new`], animating the popup with `transition`.
+    pub fn with_transition<W: 'static + Widget<T>, DW: Widget<T> + 'static>(
+        header: W,
+        make_drop: impl Fn(&T, &Env) -> DW + 'static,
+        transition: PopupTransition,
     ) -> impl Widget<T> {
         // padding for putting header in separate WidgetPod
         // because notifications from same WidgetPod are not sent
         header.padding(0.).controller(Dropdown {
             drop: Box::new(move |d, e| make_drop(d, e).boxed()),
-            window: None,
+            popup: Popup::new(DROPDOWN_CLOSED).with_transition(transition),
         })
     }
 
     fn show_dropdown(&mut self, data: &mut T, env: &Env, ctx: &mut EventCtx) {
-        let widget = (self.drop)(data, env);
-
-        let size = ctx.size();
-        let widget_screen_coordinates = ctx.to_screen(Point::new(size.width, size.height));
-        let final_coordinates = Point {
-            x: widget_screen_coordinates.x - size.width,
-            y: widget_screen_coordinates.y,
-        };
-
-        // println!("size: {}", size);
-        // println!("widget_screen_coordinates: {}", widget_screen_coordinates);
-        // println!("final_coordinates: {}", final_coordinates);
-
-        let parent_id = ctx.widget_id();
-
-        self.window = Some(
-            ctx.new_sub_window(
-                WindowConfig::default()
-                    .set_level(WindowLevel::DropDown(ctx.window().clone()))
-                    .set_position(final_coordinates)
-                    .window_size_policy(WindowSizePolicy::Content)
-                    .resizable(false)
-                    .show_titlebar(false),
-                widget.controller(DropedCtrl { parent: parent_id }),
-                data.clone(),
-                env.clone(),
-            ),
-        );
-
+        let env = theme::completed(env);
+        let widget = (self.drop)(data, &env);
+        self.popup.open(ctx, theme::popup_frame(widget), data, env);
         ctx.set_active(true);
     }
-}
-
-struct DropedCtrl {
-    parent: WidgetId,
-}
-
-// Dropdown popup
-impl<T: Data, W: Widget<T>> Controller<T, W> for DropedCtrl {
-    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
-        if let Event::MouseMove(_x) = event {
-            // ctx.submit_command(DROPDOWN_CLOSED.to(self.parent));
-        } else {
-            println!("Dropdown content received event {:?}", event);
-        }
 
-        if let Event::WindowDisconnected = event {
-            ctx.submit_command(DROPDOWN_CLOSED.to(self.parent));
-        }
-
-        child.event(ctx, event, data, env);
+    fn hide_dropdown(&self, ctx: &mut EventCtx) {
+        self.popup.dismiss(ctx);
     }
 
-    fn lifecycle(
-        &mut self,
-        child: &mut W,
-        ctx: &mut LifeCycleCtx,
-        event: &LifeCycle,
-        data: &T,
-        env: &Env,
-    ) {
-        // println!("event: {:?}", event);
-
-        println!("Dropdown content received lifecycle event: {:?}", event);
-
-        // pass the lifecycle event down to the child widget !
-        child.lifecycle(ctx, event, data, env);
+    fn span(&self, ctx: &EventCtx) -> Span {
+        let state = if self.popup.is_open() {
+            "open"
+        } else {
+            "closed"
+        };
+        debug_span!(
+            "dropdown",
+            widget = ?ctx.widget_id(),
+            window = ?ctx.window_id(),
+            popup = ?self.popup.window(),
+            state,
+        )
     }
 }
 
 // Dropdown container
 impl<T: Data, W: Widget<T>> Controller<T, W> for Dropdown<T> {
     fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
-        match event {
-            Event::Notification(n) if n.is(DROPDOWN_SHOW) && self.window.is_none() => {
-                println!("notf DROPDOWN_SHOW");
+        let _span = self.span(ctx).entered();
+
+        crate::match_command!(event, ctx => {
+            Notification(DROPDOWN_SHOW) if !self.popup.is_open() => {
+                debug!("show requested by notification");
                 self.show_dropdown(data, env, ctx);
-                ctx.set_handled();
-            }
-            Event::Notification(cmd) if cmd.is(DROPDOWN_HIDE) => {
-                println!("notf DROPDOWN_HIDE");
-                if let Some(w) = self.window {
-                    ctx.submit_command(CLOSE_WINDOW.to(w));
-                }
-                ctx.set_handled();
             }
-
-            // we recieve global mouse downs when widget is_active
-            // close on any outside mouse click
-            Event::MouseDown(ev) if ctx.is_active() && !ctx.size().to_rect().contains(ev.pos) => {
-                println!("mousedown DROPDOWN_HIDE");
-                if let Some(w) = self.window {
-                    ctx.submit_command(CLOSE_WINDOW.to(w));
-                }
+            Notification(DROPDOWN_HIDE) => {
+                debug!("hide requested by notification");
+                self.hide_dropdown(ctx);
             }
-            Event::Command(cmd) if cmd.is(DROPDOWN_SHOW) && self.window.is_none() => {
-                println!("cmd DROPDOWN_SHOW");
+            Command(DROPDOWN_SHOW) if !self.popup.is_open() => {
+                debug!("show requested by command");
                 self.show_dropdown(data, env, ctx);
-                ctx.set_handled();
             }
-            Event::Command(cmd) if cmd.is(DROPDOWN_CLOSED) => {
-                println!("cmd DROPDOWN_CLOSED");
+            Command(DROPDOWN_CLOSED(window)) if self.popup.window() == Some(*window) => {
+                debug!("popup closed");
                 ctx.set_active(false);
-                self.window = None;
-                let inner_cmd = cmd.clone().to(Target::Global);
+                self.popup.closed();
                 // send DROP_END to header
+                let inner_cmd = DROPDOWN_CLOSED.with(*window).to(Target::Global);
                 child.event(ctx, &Event::Command(inner_cmd), data, env);
-                ctx.set_handled();
             }
-            Event::Command(cmd) if cmd.is(DROPDOWN_HIDE) => {
-                println!("cmd DROPDOWN_HIDE");
-                if let Some(w) = self.window {
-                    ctx.submit_command(CLOSE_WINDOW.to(w));
-                }
-                ctx.set_handled();
+            Command(DROPDOWN_HIDE) => {
+                debug!("hide requested by command");
+                self.hide_dropdown(ctx);
             }
 
-            _ => {}
-        }
+            // we recieve global mouse downs when widget is_active
+            // close on any outside mouse click
+            _ => {
+                if let Event::MouseDown(ev) = event
+                    && ctx.is_active()
+                    && !ctx.size().to_rect().contains(ev.pos)
+                {
+                    debug!("mouse down outside, hiding popup");
+                    self.hide_dropdown(ctx);
+                }
+            }
+        });
         child.event(ctx, event, data, env);
     }
 
@@ -164,7 +128,15 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Dropdown<T> {
         data: &T,
         env: &Env,
     ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.popup.subscribe(ctx);
+        }
         // pass the lifecycle event down to the child widget !
         child.lifecycle(ctx, event, data, env)
     }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.popup.refresh(ctx);
+        child.update(ctx, old_data, data, env)
+    }
 }
@@ -3,12 +3,14 @@
 
 /// Generates `Selector`s based on module, line and column
 /// ```
-/// # use druid_widget_nursery::selectors;
+/// # use druid_gio_widgets::selectors;
 /// selectors! {
 ///     /// Foo the baz
 ///     FOO,
 ///     /// Bar the qux yay much
 ///     BAR: usize,
+///     /// Only for this crate
+///     pub(crate) BAZ,
 /// }
 /// ```
 /// expands to
@@ -18,33 +20,44 @@
 /// pub const FOO: Selector = Selector::new("path::to::module::FOO@0:0");
 /// /// Bar the qux yay much
 /// pub const BAR: Selector<usize> = Selector::new("path::to::module::BAR@0:0");
+/// /// Only for this crate
+/// pub(crate) const BAZ: Selector = Selector::new("path::to::module::BAZ@0:0");
 /// ```
+/// Selectors without a visibility are `pub`.
 #[macro_export]
 macro_rules! selectors {
+    () => {};
+    (@item [$(#[$attr:meta])*] [$vis:vis] $name:ident [$($ty:ty)?]) => {
+        $(#[$attr])*
+        $vis const $name: ::druid::Selector<$($ty)?> = ::druid::Selector::new(concat!(
+            module_path!(),
+            "::",
+            stringify!($name),
+            "@",
+            line!(),
+            ":",
+            column!()
+        ));
+    };
     (
-        $(
-            $(#[$attr:meta])*
-            $name:ident $( : $ty:ty)?
-        ),* $(,)?
+        $(#[$attr:meta])*
+        $name:ident $(: $ty:ty)? $(, $($rest:tt)*)?
     ) => {
-        $(
-            $(#[$attr])*
-            pub const $name: ::druid::Selector<$($ty)?> = ::druid::Selector::new(concat!(
-                module_path!(),
-                "::",
-                stringify!($name),
-                "@",
-                line!(),
-                ":",
-                column!()
-            ));
-        )*
+        $crate::selectors!(@item [$(#[$attr])*] [pub] $name [$($ty)?]);
+        $($crate::selectors!($($rest)*);)?
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis $name:ident $(: $ty:ty)? $(, $($rest:tt)*)?
+    ) => {
+        $crate::selectors!(@item [$(#[$attr])*] [$vis] $name [$($ty)?]);
+        $($crate::selectors!($($rest)*);)?
     };
 }
 
 /// Generates `Key`s based on module, line and column
 /// ```
-/// # use druid_widget_nursery::keys;
+/// # use druid_gio_widgets::keys;
 /// keys! {
 ///     /// height of the bar
 ///     BAR: usize,
@@ -79,35 +92,133 @@ macro_rules! keys {
     };
 }
 
-/// Matches on a command or notification.
+/// Matches on a command, a notification or an [`Event`] carrying either.
+///
+/// Arms written as `SELECTOR` or `SELECTOR(binding)` test the value directly, so it
+/// has to be a `&Command` or `&Notification`. Arms written as `Command(..)` or
+/// `Notification(..)` destructure an `&Event` instead. Any arm can carry an
+/// `if` guard, which can use the payload binding.
+///
+/// The whole invocation is an expression. If no fallback `_ =>` arm is given,
+/// unmatched values evaluate to `()`. Passing the context as `match_command!(event, ctx => ..)`
+/// calls `ctx.set_handled()` whenever a selector arm matched.
 ///
 /// # Example
 ///
 /// ```
-/// use druid_widget_nursery::{selectors, match_command};
+/// use druid_gio_widgets::{selectors, match_command};
 /// selectors!(FOO: i32, BAR);
 /// # let cmd = FOO.with(0);
-/// match_command!(cmd => {
-///     FOO(i) => {
+/// let doubled = match_command!(cmd => {
+///     FOO(i) if *i > 0 => i * 2,
+///     BAR => 0,
+///     _ => -1,
+/// });
+///
+/// # fn event(ctx: &mut druid::EventCtx, event: &druid::Event) {
+/// match_command!(event, ctx => {
+///     Notification(BAR) => {
 ///         // do something
-///     },
-///     BAR => { todo!() },
+///     }
+///     Command(FOO(i)) => tracing::info!(i, "got a foo"),
 /// });
+/// # }
 /// ```
+///
+/// [`Event`]: druid::Event
 #[macro_export]
 macro_rules! match_command {
-    ($val:expr => {$($selector:ident $(($bind:ident))? => $body:expr),+ $(,)? }) => {
+    // every arm has been consumed, emit the match
+    (@munch ($val:expr) $ctx:tt [$($acc:tt)*] _ => $body:expr $(,)?) => {
+        match $val {
+            $($acc)*
+            _ => $body,
+        }
+    };
+    (@munch ($val:expr) $ctx:tt [$($acc:tt)*]) => {
         match $val {
-            val => match () {
-                $(
-                    // TODO: druid should expose the raw selector so we can match it directly
-                    () if val.is($selector) => {
-                        $(let $bind = val.get_unchecked($selector);)?
-                        $body
-                    }
-                )+
-                    _ => {}
+            $($acc)*
+            _ => {}
+        }
+    };
+
+    // split off the next arm, block bodies don't need a trailing comma
+    (@munch $val:tt $ctx:tt $acc:tt
+        $head:ident $(($($args:tt)*))? $(if $guard:expr)? => $body:expr, $($rest:tt)*
+    ) => {
+        $crate::match_command!(@arm $val $ctx $acc [$head $(($($args)*))?] [$($guard)?] ($body) $($rest)*)
+    };
+    (@munch $val:tt $ctx:tt $acc:tt
+        $head:ident $(($($args:tt)*))? $(if $guard:expr)? => $body:block $($rest:tt)*
+    ) => {
+        $crate::match_command!(@arm $val $ctx $acc [$head $(($($args)*))?] [$($guard)?] ($body) $($rest)*)
+    };
+    (@munch $val:tt $ctx:tt $acc:tt
+        $head:ident $(($($args:tt)*))? $(if $guard:expr)? => $body:expr
+    ) => {
+        $crate::match_command!(@arm $val $ctx $acc [$head $(($($args)*))?] [$($guard)?] ($body))
+    };
+
+    (@arm $val:tt $ctx:tt [$($acc:tt)*]
+        [Command($sel:ident $(($bind:pat))?)] [$($guard:expr)?] ($body:expr) $($rest:tt)*
+    ) => {
+        $crate::match_command!(@munch $val $ctx [
+            $($acc)*
+            ::druid::Event::Command(__cmd)
+                if $crate::match_command!(@test __cmd, $sel $(($bind))?; $($guard)?) =>
+            {
+                $crate::match_command!(@body __cmd, $ctx, $sel $(($bind))?; $body)
             }
+        ] $($rest)*)
+    };
+    (@arm $val:tt $ctx:tt [$($acc:tt)*]
+        [Notification($sel:ident $(($bind:pat))?)] [$($guard:expr)?] ($body:expr) $($rest:tt)*
+    ) => {
+        $crate::match_command!(@munch $val $ctx [
+            $($acc)*
+            ::druid::Event::Notification(__cmd)
+                if $crate::match_command!(@test __cmd, $sel $(($bind))?; $($guard)?) =>
+            {
+                $crate::match_command!(@body __cmd, $ctx, $sel $(($bind))?; $body)
+            }
+        ] $($rest)*)
+    };
+    (@arm $val:tt $ctx:tt [$($acc:tt)*]
+        [$sel:ident $(($bind:pat))?] [$($guard:expr)?] ($body:expr) $($rest:tt)*
+    ) => {
+        $crate::match_command!(@munch $val $ctx [
+            $($acc)*
+            ref __cmd if $crate::match_command!(@test __cmd, $sel $(($bind))?; $($guard)?) => {
+                $crate::match_command!(@body __cmd, $ctx, $sel $(($bind))?; $body)
+            }
+        ] $($rest)*)
+    };
+
+    // the guard needs the payload, so bind it once here and again in the body
+    (@test $cmd:ident, $sel:ident ($bind:pat); $guard:expr) => {
+        match $cmd.get($sel) {
+            #[allow(unused_variables)]
+            Some($bind) => $guard,
+            None => false,
         }
     };
+    (@test $cmd:ident, $sel:ident $(($bind:pat))?; $($guard:expr)?) => {
+        $cmd.is($sel) $(&& $guard)?
+    };
+
+    (@body $cmd:ident, ($($ctx:ident)?), $sel:ident $(($bind:pat))?; $body:expr) => {{
+        $($ctx.set_handled();)?
+        $(
+            #[allow(unused_variables)]
+            let $bind = $cmd.get($sel).unwrap();
+        )?
+        $body
+    }};
+
+    ($val:expr, $ctx:ident => { $($arms:tt)* }) => {
+        $crate::match_command!(@munch ($val) ($ctx) [] $($arms)*)
+    };
+    ($val:expr => { $($arms:tt)* }) => {
+        $crate::match_command!(@munch ($val) () [] $($arms)*)
+    };
 }
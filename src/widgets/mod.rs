@@ -1,8 +1,37 @@
+#[cfg(feature = "button")]
 pub mod advanced_button;
-pub mod advanced_dropdown;
-pub mod advanced_select;
+#[cfg(feature = "bus")]
+pub mod bus;
+#[cfg(feature = "color-picker")]
+pub mod color_picker;
+#[cfg(feature = "date-picker")]
+pub mod date_picker;
+#[cfg(feature = "disclosure")]
+pub mod disclosure;
+#[cfg(feature = "dropdown")]
 pub mod dropdown;
+#[cfg(feature = "number-input")]
+pub mod number_input;
+#[cfg(feature = "popover")]
+pub mod popover;
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+pub mod popup;
+#[cfg(feature = "select")]
 pub mod select;
+#[cfg(feature = "slider")]
+pub mod slider;
+#[cfg(feature = "table")]
+pub mod table;
+#[cfg(feature = "tabs")]
+pub mod tabs;
+#[cfg(feature = "toast")]
+pub mod toast;
+#[cfg(feature = "toggle")]
+pub mod toggle;
+#[cfg(feature = "tree")]
+pub mod tree;
+#[cfg(feature = "virtual-list")]
+pub mod virtual_list;
 
 #[macro_use]
 mod macros;
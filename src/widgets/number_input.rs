@@ -0,0 +1,400 @@
+//! A number field with buttons stepping the value down and up.
+//!
+//! [`NumberInput`] binds to an `f64` or an `i64`. The value stays within its range and
+//! shows with a fixed number of decimals and a unit when asked to. Holding a button down
+//! keeps stepping, and so does the mouse wheel over the field.
+//!
+//! Text that isn't a number in range is outlined with the error color and never reaches
+//! the data; leaving the field puts the last good value back.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use druid::text::{Formatter, Selection, Validation, ValidationError};
+use druid::widget::prelude::*;
+use druid::widget::{Controller, Flex, TextBox, TextBoxEvent, ValidationDelegate};
+use druid::{Point, TimerToken, WidgetExt, WidgetPod};
+
+use crate::theme;
+use crate::widgets::advanced_button::AdvancedButton;
+
+crate::selectors! {
+    /// Steps the value by the given number of steps, sent up by the buttons
+    pub(crate) STEP: f64,
+}
+
+/// How long a button is held before it starts repeating
+const REPEAT_DELAY: Duration = Duration::from_millis(400);
+const REPEAT_INTERVAL: Duration = Duration::from_millis(60);
+
+/// What a [`NumberInput`] can bind to, an `f64` or an `i64`.
+pub trait NumberValue: Data + Copy + fmt::Debug + fmt::Display {
+    fn parse(text: &str) -> Result<Self, ValidationError>;
+    fn to_f64(self) -> f64;
+    /// The value nearest to `value`.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl NumberValue for f64 {
+    fn parse(text: &str) -> Result<Self, ValidationError> {
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            Ok(_) => Err(ValidationError::new(OutOfRange(f64::MIN, f64::MAX))),
+            Err(err) => Err(ValidationError::new(err)),
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl NumberValue for i64 {
+    fn parse(text: &str) -> Result<Self, ValidationError> {
+        text.parse::<i64>().map_err(ValidationError::new)
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value.round() as i64
+    }
+}
+
+/// A number outside the range of a [`NumberInput`].
+#[derive(Debug, Clone, Copy)]
+struct OutOfRange(f64, f64);
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected a number from {} to {}", self.0, self.1)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// What the builder methods set, shared with the formatter and the buttons.
+struct Settings {
+    min: f64,
+    max: f64,
+    step: f64,
+    precision: Option<usize>,
+    unit: String,
+}
+
+impl Settings {
+    fn clamp(&self, value: f64) -> f64 {
+        let value = match self.precision {
+            // keeps steps of 0.1 from drifting to 0.30000000000000004
+            Some(precision) => {
+                let scale = 10f64.powi(precision as i32);
+                (value * scale).round() / scale
+            }
+            None => value,
+        };
+        value.clamp(self.min, self.max)
+    }
+}
+
+/// Reads and writes the values of a [`NumberInput`], following its range, precision
+/// and unit.
+pub struct NumberFormatter(Rc<RefCell<Settings>>);
+
+impl<T: NumberValue> Formatter<T> for NumberFormatter {
+    fn format(&self, value: &T) -> String {
+        let settings = self.0.borrow();
+        let number = self.format_for_editing(value);
+        if settings.unit.is_empty() {
+            number
+        } else {
+            format!("{number} {}", settings.unit)
+        }
+    }
+
+    fn format_for_editing(&self, value: &T) -> String {
+        match self.0.borrow().precision {
+            Some(precision) => format!("{:.*}", precision, value.to_f64()),
+            None => value.to_string(),
+        }
+    }
+
+    fn validate_partial_input(&self, _input: &str, _sel: &Selection) -> Validation {
+        // half typed numbers stay in the field, outlined, until they make sense
+        Validation::success()
+    }
+
+    fn value(&self, input: &str) -> Result<T, ValidationError> {
+        let settings = self.0.borrow();
+        let input = input.trim();
+        let number = input
+            .strip_suffix(settings.unit.as_str())
+            .filter(|_| !settings.unit.is_empty())
+            .unwrap_or(input)
+            .trim_end();
+        let value = T::parse(number)?;
+        if (settings.min..=settings.max).contains(&value.to_f64()) {
+            Ok(value)
+        } else {
+            Err(ValidationError::new(OutOfRange(settings.min, settings.max)))
+        }
+    }
+}
+
+/// Keeps track of whether the text in the field makes a value.
+struct Validity<T> {
+    formatter: NumberFormatter,
+    invalid: Rc<Cell<bool>>,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: NumberValue> ValidationDelegate for Validity<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: TextBoxEvent, current_text: &str) {
+        let invalid = match event {
+            TextBoxEvent::Began | TextBoxEvent::Complete | TextBoxEvent::Cancel => false,
+            TextBoxEvent::Changed | TextBoxEvent::PartiallyInvalid(_) => {
+                Formatter::<T>::value(&self.formatter, current_text).is_err()
+            }
+            TextBoxEvent::Invalid(_) => true,
+        };
+        if self.invalid.replace(invalid) != invalid {
+            ctx.request_paint();
+        }
+    }
+}
+
+/// Outlines its child with the error color while `invalid` is set.
+struct ErrorRing<W> {
+    child: W,
+    invalid: Rc<Cell<bool>>,
+}
+
+impl<T, W: Widget<T>> Widget<T> for ErrorRing<W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, old_data, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.child.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if self.invalid.get() {
+            let width = theme::get(env, theme::ACTIVE_BORDER_WIDTH);
+            let rect = ctx
+                .size()
+                .to_rect()
+                .inset(-width / 2.)
+                .to_rounded_rect(theme::get(env, theme::CORNER_RADIUS));
+            ctx.stroke(rect, &theme::get(env, theme::ERROR), width);
+        }
+    }
+}
+
+/// Sends [`STEP`] when its button is pressed, and again and again while it is held.
+struct Repeat {
+    steps: f64,
+    timer: TimerToken,
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for Repeat {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(_) if !ctx.is_disabled() => {
+                ctx.submit_notification(STEP.with(self.steps));
+                self.timer = ctx.request_timer(REPEAT_DELAY);
+            }
+            Event::Timer(token) if *token == self.timer => {
+                if ctx.is_active() && !ctx.is_disabled() {
+                    ctx.submit_notification(STEP.with(self.steps));
+                    self.timer = ctx.request_timer(REPEAT_INTERVAL);
+                } else {
+                    self.timer = TimerToken::INVALID;
+                }
+            }
+            Event::MouseUp(_) => self.timer = TimerToken::INVALID,
+            _ => {}
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
+pub struct NumberInput<T> {
+    inner: WidgetPod<T, Box<dyn Widget<T>>>,
+    settings: Rc<RefCell<Settings>>,
+    /// Steps waiting for the field to finish editing, which would overwrite them
+    pending: Option<f64>,
+}
+
+impl<T: NumberValue> NumberInput<T> {
+    /// A field taking any number, stepping by 1.
+    pub fn new() -> Self {
+        let settings = Rc::new(RefCell::new(Settings {
+            min: f64::MIN,
+            max: f64::MAX,
+            step: 1.,
+            precision: None,
+            unit: String::new(),
+        }));
+        let invalid = Rc::new(Cell::new(false));
+        let text = TextBox::new()
+            .with_formatter(NumberFormatter(settings.clone()))
+            .update_data_while_editing(true)
+            .delegate(Validity::<T> {
+                formatter: NumberFormatter(settings.clone()),
+                invalid: invalid.clone(),
+                phantom: std::marker::PhantomData,
+            });
+        let button = |label: &'static str, steps: f64| {
+            let settings = settings.clone();
+            AdvancedButton::new(label)
+                .controller(Repeat {
+                    steps,
+                    timer: TimerToken::INVALID,
+                })
+                .disabled_if(move |data: &T, _| {
+                    let settings = settings.borrow();
+                    let limit = if steps < 0. {
+                        settings.min
+                    } else {
+                        settings.max
+                    };
+                    data.to_f64() == limit
+                })
+        };
+        let row = Flex::row()
+            .with_child(button("−", -1.))
+            .with_flex_child(
+                ErrorRing {
+                    child: text,
+                    invalid,
+                },
+                1.,
+            )
+            .with_child(button("+", 1.));
+        NumberInput {
+            inner: WidgetPod::new(row.boxed()),
+            settings,
+            pending: None,
+        }
+    }
+
+    /// Builder-style method to keep the value from `min` to `max`.
+    pub fn with_range(self, min: f64, max: f64) -> Self {
+        {
+            let mut settings = self.settings.borrow_mut();
+            settings.min = min;
+            settings.max = max;
+        }
+        self
+    }
+
+    /// Builder-style method to set how much the buttons and the wheel change the value.
+    pub fn with_step(self, step: f64) -> Self {
+        self.settings.borrow_mut().step = step;
+        self
+    }
+
+    /// Builder-style method to show the value with `precision` decimals.
+    pub fn with_precision(self, precision: usize) -> Self {
+        self.settings.borrow_mut().precision = Some(precision);
+        self
+    }
+
+    /// Builder-style method to show `unit` after the value, which typing may leave out.
+    pub fn with_unit(self, unit: impl Into<String>) -> Self {
+        self.settings.borrow_mut().unit = unit.into();
+        self
+    }
+
+    /// The formatter of the field, to show or read values the same way elsewhere.
+    pub fn formatter(&self) -> NumberFormatter {
+        NumberFormatter(self.settings.clone())
+    }
+
+    /// Steps the value now, or once the field has committed the text being edited.
+    fn request_step(&mut self, ctx: &mut EventCtx, data: &mut T, steps: f64) {
+        if ctx.has_focus() {
+            // the field commits its text once focus leaves, then the step applies
+            self.pending = Some(self.pending.unwrap_or(0.) + steps);
+            ctx.resign_focus();
+        } else {
+            self.step(data, steps);
+        }
+    }
+
+    fn step(&self, data: &mut T, steps: f64) {
+        let settings = self.settings.borrow();
+        let value = settings.clamp(data.to_f64() + steps * settings.step);
+        *data = T::from_f64(value);
+    }
+}
+
+impl<T: NumberValue> Default for NumberInput<T> {
+    fn default() -> Self {
+        NumberInput::new()
+    }
+}
+
+impl<T: NumberValue> Widget<T> for NumberInput<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Wheel(wheel) = event
+            && !ctx.is_disabled()
+            && wheel.wheel_delta.y != 0.
+        {
+            // scrolling up counts up
+            self.request_step(ctx, data, -wheel.wheel_delta.y.signum());
+            ctx.set_handled();
+            return;
+        }
+        crate::match_command!(event, ctx => {
+            Notification(STEP(steps)) => {
+                self.request_step(ctx, data, *steps);
+                return;
+            }
+            _ => {}
+        });
+        self.inner.event(ctx, event, data, env);
+        if let Event::Command(_) = event
+            && !ctx.has_focus()
+            && let Some(steps) = self.pending.take()
+        {
+            self.step(data, steps);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.inner.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+}
@@ -0,0 +1,342 @@
+//! A popup window anchored to any widget.
+//!
+//! [`Popover`] wraps the anchor and opens its content below it, on a click, while the
+//! pointer is over the anchor, or while the anchor has keyboard focus. The content runs
+//! in a sub-window bound to the same data as the anchor, so it can edit it directly.
+
+use std::time::Duration;
+
+use druid::kurbo::BezPath;
+use druid::widget::prelude::*;
+use druid::widget::{Controller, CrossAxisAlignment, Flex, Label};
+use druid::{Point, TimerToken, WidgetExt, WidgetPod, WindowId};
+use tracing::{Span, debug, debug_span};
+
+use crate::theme;
+use crate::widgets::popup::{POPUP_DISMISS, Popup, PopupTransition};
+
+/// Height of the arrow pointing at the anchor, its base is twice as wide.
+const ARROW: f64 = 6.;
+/// How long the pointer may be away from both the anchor and the popover before a
+/// hover popover closes, enough to cross the gap between them.
+const HOVER_GRACE: Duration = Duration::from_millis(250);
+
+type ContentFn<T> = Box<dyn Fn(&T, &Env) -> Box<dyn Widget<T>>>;
+
+crate::selectors! {
+    POPOVER_SHOW,
+    POPOVER_HIDE,
+    /// Published on the bus when the popover window with this id goes away
+    POPOVER_CLOSED: WindowId,
+}
+
+crate::selectors! {
+    /// Sent from the popover window to its anchor when the pointer enters or leaves it
+    pub(crate) CONTENT_HOT: bool,
+}
+
+/// What opens a [`Popover`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopoverTrigger {
+    /// A click on the anchor toggles it, a click anywhere else closes it
+    Click,
+    /// Opens while the pointer is over the anchor or the popover
+    Hover,
+    /// Opens while the anchor has keyboard focus
+    Focus,
+    /// Only [`POPOVER_SHOW`] and [`POPOVER_HIDE`] open and close it
+    Manual,
+}
+
+pub struct Popover<T> {
+    anchor: WidgetPod<T, Box<dyn Widget<T>>>,
+    content: ContentFn<T>,
+    trigger: PopoverTrigger,
+    arrow: bool,
+    close_button: bool,
+    popup: Popup,
+    content_hot: bool,
+    hover_timer: TimerToken,
+}
+
+impl<T: Data> Popover<T> {
+    /// Opens `make_content` below `anchor` when the anchor is clicked.
+    ///
+    /// Like with [`Dropdown`](crate::widgets::dropdown::Dropdown), anything inside the
+    /// anchor can also open and close it by submitting [`POPOVER_SHOW`] or
+    /// [`POPOVER_HIDE`] as notifications.
+    pub fn new<W: Widget<T> + 'static, CW: Widget<T> + 'static>(
+        anchor: W,
+        make_content: impl Fn(&T, &Env) -> CW + 'static,
+    ) -> Self {
+        Popover {
+            anchor: WidgetPod::new(anchor.boxed()),
+            content: Box::new(move |data, env| make_content(data, env).boxed()),
+            trigger: PopoverTrigger::Click,
+            arrow: true,
+            close_button: false,
+            popup: Popup::new(POPOVER_CLOSED),
+            content_hot: false,
+            hover_timer: TimerToken::INVALID,
+        }
+    }
+
+    pub fn with_trigger(mut self, trigger: PopoverTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Leaves out the arrow pointing at the anchor.
+    pub fn without_arrow(mut self) -> Self {
+        self.arrow = false;
+        self
+    }
+
+    /// Puts a button closing the popover in its top right corner.
+    pub fn with_close_button(mut self) -> Self {
+        self.close_button = true;
+        self
+    }
+
+    pub fn with_transition(mut self, transition: PopupTransition) -> Self {
+        self.popup = self.popup.with_transition(transition);
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_open()
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        let env = theme::completed(env);
+        let mut content = (self.content)(data, &env);
+        if self.close_button {
+            content = Flex::row()
+                .with_child(content)
+                .with_spacer(theme::get(&env, theme::SPACING))
+                .with_child(close_button())
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .boxed();
+        }
+        let owner = ctx.widget_id();
+        let mut popup = theme::popup_frame(content)
+            .controller(PopoverCtrl { owner })
+            .boxed();
+        if self.arrow {
+            // points at the middle of the anchor, unless the anchor is wider than that
+            let x = (ctx.size().width / 2.).max(ARROW * 2.);
+            popup = Arrow::new(popup, x).boxed();
+        }
+
+        self.popup.open(ctx, popup, data, env);
+        if self.trigger == PopoverTrigger::Click {
+            // being active sends us mouse downs from anywhere in the window
+            ctx.set_active(true);
+        }
+    }
+
+    fn hide(&mut self, ctx: &mut EventCtx) {
+        self.popup.dismiss(ctx);
+    }
+
+    fn span(&self, ctx: &EventCtx) -> Span {
+        debug_span!(
+            "popover",
+            widget = ?ctx.widget_id(),
+            trigger = ?self.trigger,
+            popup = ?self.popup.window(),
+        )
+    }
+}
+
+fn close_button<T: Data>() -> impl Widget<T> {
+    Label::new("✕")
+        .with_text_size(11.)
+        .on_click(|ctx, _, _| ctx.submit_command(POPUP_DISMISS.to(ctx.window_id())))
+}
+
+impl<T: Data> Widget<T> for Popover<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let _span = self.span(ctx).entered();
+
+        crate::match_command!(event, ctx => {
+            Notification(POPOVER_SHOW) if !self.popup.is_open() => {
+                debug!("show requested by notification");
+                self.show(ctx, data, env);
+            }
+            Notification(POPOVER_HIDE) => {
+                debug!("hide requested by notification");
+                self.hide(ctx);
+            }
+            Command(POPOVER_SHOW) if !self.popup.is_open() => {
+                debug!("show requested by command");
+                self.show(ctx, data, env);
+            }
+            Command(POPOVER_HIDE) => {
+                debug!("hide requested by command");
+                self.hide(ctx);
+            }
+            Command(POPOVER_CLOSED(window)) if self.popup.window() == Some(*window) => {
+                debug!("popover closed");
+                if self.trigger == PopoverTrigger::Click {
+                    ctx.set_active(false);
+                }
+                self.popup.closed();
+                self.content_hot = false;
+            }
+            Command(CONTENT_HOT(hot)) => {
+                self.content_hot = *hot;
+                if !hot {
+                    self.hover_timer = ctx.request_timer(HOVER_GRACE);
+                }
+            }
+            _ => {
+                match event {
+                    Event::MouseDown(ev) if self.trigger == PopoverTrigger::Click => {
+                        let inside = ctx.size().to_rect().contains(ev.pos);
+                        if self.popup.is_open() {
+                            // the same click that closes it must not reopen it
+                            debug!(inside, "mouse down while open, hiding popover");
+                            self.hide(ctx);
+                        } else if inside {
+                            self.show(ctx, data, env);
+                        }
+                    }
+                    Event::Timer(token) if *token == self.hover_timer => {
+                        self.hover_timer = TimerToken::INVALID;
+                        if !ctx.is_hot() && !self.content_hot {
+                            debug!("pointer left, hiding popover");
+                            self.hide(ctx);
+                        }
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+        });
+        self.anchor.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.popup.subscribe(ctx);
+        }
+        let had_focus = self.anchor.has_focus();
+        self.anchor.lifecycle(ctx, event, data, env);
+
+        // opening needs an `EventCtx`, so the triggers ask through commands to ourselves
+        let target = ctx.widget_id();
+        match (self.trigger, event) {
+            (PopoverTrigger::Hover, LifeCycle::HotChanged(true)) if !self.popup.is_open() => {
+                ctx.submit_command(POPOVER_SHOW.to(target));
+            }
+            (PopoverTrigger::Hover, LifeCycle::HotChanged(false)) if self.popup.is_open() => {
+                self.hover_timer = ctx.request_timer(HOVER_GRACE);
+            }
+            (PopoverTrigger::Focus, _) if had_focus != self.anchor.has_focus() => {
+                if self.anchor.has_focus() {
+                    ctx.submit_command(POPOVER_SHOW.to(target));
+                } else {
+                    ctx.submit_command(POPOVER_HIDE.to(target));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.popup.refresh(ctx);
+        self.anchor.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.anchor.layout(ctx, bc, data, env);
+        self.anchor.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.anchor.baseline_offset());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.anchor.paint(ctx, data, env);
+    }
+}
+
+// Popover window
+struct PopoverCtrl {
+    owner: WidgetId,
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for PopoverCtrl {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(hot) = event {
+            ctx.submit_command(CONTENT_HOT.with(*hot).to(self.owner));
+        }
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+/// Leaves room above the popup frame for an arrow at `x` and draws it over the frame's
+/// top border.
+struct Arrow<T> {
+    frame: WidgetPod<T, Box<dyn Widget<T>>>,
+    x: f64,
+}
+
+impl<T: Data> Arrow<T> {
+    fn new(frame: impl Widget<T> + 'static, x: f64) -> Self {
+        Arrow {
+            frame: WidgetPod::new(frame.boxed()),
+            x,
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Arrow<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.frame.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.frame.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.frame.update(ctx, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let bc = bc.shrink((0., ARROW));
+        let size = self.frame.layout(ctx, &bc, data, env);
+        self.frame.set_origin(ctx, Point::new(0., ARROW));
+        Size::new(size.width, size.height + ARROW)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.frame.paint(ctx, data, env);
+
+        // keep clear of the rounded corners
+        let radius = theme::get(env, theme::CORNER_RADIUS).as_single_radius();
+        let inset = radius.unwrap_or(0.) + ARROW;
+        let x = self.x.min(ctx.size().width - inset).max(inset);
+
+        let mut outline = BezPath::new();
+        outline.move_to((x - ARROW, ARROW));
+        outline.line_to((x, 0.));
+        outline.line_to((x + ARROW, ARROW));
+        // one pixel further down than the outline, to cover the frame's border
+        let mut fill = outline.clone();
+        fill.line_to((x + ARROW, ARROW + 1.));
+        fill.line_to((x - ARROW, ARROW + 1.));
+        fill.close_path();
+
+        ctx.fill(fill, &theme::get(env, theme::POPUP_BACKGROUND));
+        ctx.stroke(outline, &theme::get(env, theme::BORDER), 1.);
+    }
+}
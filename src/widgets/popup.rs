@@ -0,0 +1,319 @@
+//! Popup windows opened below a widget, and their enter and exit animations.
+//!
+//! A popup is dismissed by sending [`POPUP_DISMISS`] to its window, it plays its exit
+//! animation and only then submits `CLOSE_WINDOW` for itself.
+
+use std::time::Duration;
+
+use druid::commands::CLOSE_WINDOW;
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{
+    Affine, Point, Selector, WidgetExt, WidgetPod, WindowConfig, WindowId, WindowLevel,
+    WindowSizePolicy,
+};
+use tracing::{debug, debug_span};
+
+use crate::animation::{Animated, Easing, reduced_motion};
+use crate::theme;
+use crate::widgets::bus::{Publish, Subscription};
+
+crate::selectors! {
+    /// Asks a popup window to play its exit animation and close
+    POPUP_DISMISS,
+}
+
+/// How a popup appears or goes away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupAnimation {
+    None,
+    Fade,
+    /// Slides out from under the widget that opened it
+    Slide,
+    /// Grows from the edge next to the widget that opened it, fading in
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopupTransition {
+    pub enter: PopupAnimation,
+    pub exit: PopupAnimation,
+    pub duration: Duration,
+}
+
+impl PopupTransition {
+    pub const NONE: PopupTransition = PopupTransition::new(PopupAnimation::None);
+
+    /// Plays `animation` both ways.
+    pub const fn new(animation: PopupAnimation) -> PopupTransition {
+        PopupTransition {
+            enter: animation,
+            exit: animation,
+            duration: Duration::from_millis(120),
+        }
+    }
+
+    pub const fn with_duration(mut self, duration: Duration) -> PopupTransition {
+        self.duration = duration;
+        self
+    }
+
+    fn plays(&self, animation: PopupAnimation) -> bool {
+        animation != PopupAnimation::None && !self.duration.is_zero() && !reduced_motion()
+    }
+}
+
+impl Default for PopupTransition {
+    fn default() -> Self {
+        PopupTransition::new(PopupAnimation::Fade)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Entering,
+    Shown,
+    Exiting,
+}
+
+/// Root of a popup window, plays the transition around its content.
+pub struct PopupRoot<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    transition: PopupTransition,
+    phase: Phase,
+    /// How much of the popup shows, from 0 (gone) to 1
+    shown: Animated<f64>,
+}
+
+impl<T: Data> PopupRoot<T> {
+    pub fn new(child: impl Widget<T> + 'static, transition: PopupTransition) -> Self {
+        PopupRoot {
+            child: WidgetPod::new(child.boxed()),
+            transition,
+            phase: Phase::Shown,
+            // ease out, the popup moves fast first and settles
+            shown: Animated::new(1.)
+                .with_duration(transition.duration)
+                .with_easing(Easing::EASE_OUT),
+        }
+    }
+
+    fn animation(&self) -> PopupAnimation {
+        match self.phase {
+            Phase::Exiting => self.transition.exit,
+            _ => self.transition.enter,
+        }
+    }
+
+    fn close(&self, ctx: &mut EventCtx) {
+        ctx.submit_command(CLOSE_WINDOW.to(ctx.window_id()));
+    }
+}
+
+impl<T: Data> Widget<T> for PopupRoot<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::WindowConnected if self.transition.plays(self.transition.enter) => {
+                self.phase = Phase::Entering;
+                self.shown.jump_to(0.);
+                self.shown.animate_to(1.);
+                ctx.request_anim_frame();
+            }
+            Event::Command(cmd) if cmd.is(POPUP_DISMISS) => {
+                ctx.set_handled();
+                if self.phase == Phase::Exiting {
+                    return;
+                }
+                if self.transition.plays(self.transition.exit) {
+                    debug!("popup exit animation started");
+                    self.phase = Phase::Exiting;
+                    self.shown.animate_to(0.);
+                    ctx.request_anim_frame();
+                } else {
+                    self.close(ctx);
+                }
+                return;
+            }
+            Event::AnimFrame(nanos) if self.phase != Phase::Shown => {
+                ctx.request_paint();
+                if self.shown.advance(*nanos) {
+                    ctx.request_anim_frame();
+                } else if self.phase == Phase::Exiting {
+                    self.close(ctx);
+                } else {
+                    self.phase = Phase::Shown;
+                }
+            }
+            _ => {}
+        }
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let t = self.shown.value();
+        let size = ctx.size();
+        let animation = self.animation();
+
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            match animation {
+                // popups open below the widget they belong to
+                PopupAnimation::Slide => {
+                    ctx.transform(Affine::translate((0., -(1. - t) * size.height)))
+                }
+                PopupAnimation::Scale => {
+                    let scale = 0.9 + 0.1 * t;
+                    let x = size.width / 2.;
+                    ctx.transform(
+                        Affine::translate((x, 0.))
+                            * Affine::scale(scale)
+                            * Affine::translate((-x, 0.)),
+                    )
+                }
+                PopupAnimation::None | PopupAnimation::Fade => {}
+            }
+            self.child.paint(ctx, data, env);
+        });
+
+        // there is no group opacity in piet, fade from the window background instead
+        if matches!(animation, PopupAnimation::Fade | PopupAnimation::Scale) && t < 1. {
+            let background = theme::get(env, theme::WINDOW_BACKGROUND);
+            ctx.fill(size.to_rect(), &background.with_alpha(1. - t));
+        }
+    }
+}
+
+/// The popup window of a widget, opened right below it.
+///
+/// The window publishes `channel` on the bus with its id once it goes away. The owner
+/// subscribes to it on `LifeCycle::WidgetAdded` and calls [`Popup::closed`] when it hears
+/// about its own window.
+pub(crate) struct Popup {
+    channel: Selector<WindowId>,
+    transition: PopupTransition,
+    window: Option<WindowId>,
+    subscription: Subscription,
+}
+
+impl Popup {
+    pub(crate) fn new(channel: Selector<WindowId>) -> Popup {
+        Popup {
+            channel,
+            transition: PopupTransition::default(),
+            window: None,
+            subscription: Subscription::new(),
+        }
+    }
+
+    pub(crate) fn with_transition(mut self, transition: PopupTransition) -> Popup {
+        self.transition = transition;
+        self
+    }
+
+    pub(crate) fn window(&self) -> Option<WindowId> {
+        self.window
+    }
+
+    pub(crate) fn is_open(&self) -> bool {
+        self.window.is_some()
+    }
+
+    pub(crate) fn subscribe(&self, ctx: &mut LifeCycleCtx) {
+        self.subscription.subscribe(ctx.widget_id(), self.channel);
+    }
+
+    /// Opens `content` in a window below the widget of `ctx`, `content` comes framed
+    /// already (see `theme::popup_frame`).
+    pub(crate) fn open<T: Data>(
+        &mut self,
+        ctx: &mut EventCtx,
+        content: impl Widget<T> + 'static,
+        data: &T,
+        env: Env,
+    ) {
+        let position = ctx.to_screen(Point::new(0., ctx.size().height));
+        let content = content.controller(PublishClosed(self.channel));
+        let window = ctx.new_sub_window(
+            WindowConfig::default()
+                .set_level(WindowLevel::DropDown(ctx.window().clone()))
+                .set_position(position)
+                .window_size_policy(WindowSizePolicy::Content)
+                .resizable(false)
+                .show_titlebar(false),
+            PopupRoot::new(content, self.transition),
+            data.clone(),
+            env,
+        );
+        debug!(popup = ?window, %position, "popup opened");
+        self.window = Some(window);
+    }
+
+    pub(crate) fn dismiss(&self, ctx: &mut EventCtx) {
+        if let Some(window) = self.window {
+            // the popup closes itself once its exit animation is done
+            ctx.submit_command(POPUP_DISMISS.to(window));
+        }
+    }
+
+    /// Forgets the window once it published that it went away.
+    pub(crate) fn closed(&mut self) {
+        self.window = None;
+    }
+
+    /// Passes a change of the owner's `Env` on to the window, call it from `update`.
+    pub(crate) fn refresh(&self, ctx: &mut UpdateCtx) {
+        if ctx.env_changed()
+            && let Some(window) = self.window
+        {
+            ctx.submit_command(theme::REFRESH_POPUP.to(window));
+        }
+    }
+}
+
+// Publishes the owner's channel when the popup window goes away.
+struct PublishClosed(Selector<WindowId>);
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for PublishClosed {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let _span = debug_span!("popup", window = ?ctx.window_id()).entered();
+
+        #[cfg(feature = "trace-events")]
+        if !matches!(event, Event::MouseMove(_)) {
+            tracing::trace!(?event, "popup event");
+        }
+
+        if let Event::WindowDisconnected = event {
+            debug!("popup window disconnected");
+            ctx.publish(self.0.with(ctx.window_id()));
+        }
+        child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        #[cfg(feature = "trace-events")]
+        tracing::trace!(window = ?ctx.window_id(), ?event, "popup lifecycle");
+
+        child.lifecycle(ctx, event, data, env);
+    }
+}
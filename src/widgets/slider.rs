@@ -0,0 +1,406 @@
+//! A slider over a range of numbers, drawn with the same theme keys as `AdvancedButton`.
+//!
+//! [`Slider`] binds to an `f64`, or to an `(f64, f64)` for a range with two thumbs that
+//! can't cross. It can snap to steps, draw tick marks, run vertically with the minimum at
+//! the bottom, and show the value above the thumb while it is dragged.
+//!
+//! With focus the arrow keys move the thumb by a step, PageUp and PageDown by a tenth of
+//! the range, and Home and End to the ends. In range mode they move the thumb pressed
+//! last.
+
+use druid::kurbo::{Circle, Line};
+use druid::piet::{Text, TextLayout, TextLayoutBuilder};
+use druid::widget::Axis;
+use druid::widget::prelude::*;
+use druid::{Insets, KbKey, Point, Rect};
+use tracing::warn;
+
+use crate::theme;
+
+const THUMB_RADIUS: f64 = 8.;
+const TRACK_THICKNESS: f64 = 4.;
+/// Length of the track when the constraints leave it open
+const DEFAULT_LENGTH: f64 = 160.;
+const TICK_GAP: f64 = 2.;
+const TICK_LENGTH: f64 = 4.;
+/// Room the value tooltip takes above a horizontal thumb, or left of a vertical one
+const TOOLTIP_SPACE: f64 = 28.;
+const TOOLTIP_PADDING: f64 = 4.;
+
+/// What a [`Slider`] can bind to, an `f64` or an `(f64, f64)` range.
+pub trait SliderValue: Data {
+    /// The values of the thumbs, lowest first.
+    fn thumbs(&self) -> Vec<f64>;
+    /// Moves a thumb, keeping it from crossing the others.
+    fn set_thumb(&mut self, index: usize, value: f64);
+}
+
+impl SliderValue for f64 {
+    fn thumbs(&self) -> Vec<f64> {
+        vec![*self]
+    }
+
+    fn set_thumb(&mut self, _index: usize, value: f64) {
+        *self = value;
+    }
+}
+
+impl SliderValue for (f64, f64) {
+    fn thumbs(&self) -> Vec<f64> {
+        vec![self.0, self.1]
+    }
+
+    fn set_thumb(&mut self, index: usize, value: f64) {
+        if index == 0 {
+            self.0 = value.min(self.1);
+        } else {
+            self.1 = value.max(self.0);
+        }
+    }
+}
+
+pub struct Slider<T> {
+    min: f64,
+    max: f64,
+    step: Option<f64>,
+    tick_every: Option<f64>,
+    axis: Axis,
+    format: Option<Box<dyn Fn(f64) -> String>>,
+    /// The thumb being dragged
+    dragging: Option<usize>,
+    /// The thumb the keys move, the one pressed last
+    current: usize,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: SliderValue> Slider<T> {
+    /// A horizontal slider from 0 to 1.
+    pub fn new() -> Self {
+        Slider {
+            min: 0.,
+            max: 1.,
+            step: None,
+            tick_every: None,
+            axis: Axis::Horizontal,
+            format: None,
+            dragging: None,
+            current: 0,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Builder-style method to set the values at the ends of the slider.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Builder-style method to snap values to `min` plus multiples of `step`.
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = (step > 0.).then_some(step);
+        self
+    }
+
+    /// Builder-style method to draw a tick mark every `every` from the minimum.
+    pub fn with_ticks(mut self, every: f64) -> Self {
+        self.tick_every = (every > 0.).then_some(every);
+        self
+    }
+
+    /// Builder-style method to run the slider bottom to top.
+    pub fn vertical(mut self) -> Self {
+        self.axis = Axis::Vertical;
+        self
+    }
+
+    /// Builder-style method to show the value, written by `format`, while a thumb is
+    /// dragged.
+    pub fn with_value_tooltip(mut self, format: impl Fn(f64) -> String + 'static) -> Self {
+        self.format = Some(Box::new(format));
+        self
+    }
+
+    fn snap(&self, value: f64) -> f64 {
+        let value = match self.step {
+            Some(step) => self.min + ((value - self.min) / step).round() * step,
+            None => value,
+        };
+        value.clamp(self.min, self.max)
+    }
+
+    /// The distance keys move a thumb by, and PageUp and PageDown.
+    fn key_steps(&self) -> (f64, f64) {
+        let range = self.max - self.min;
+        let step = self.step.unwrap_or(range / 100.);
+        (step, (range / 10.).max(step))
+    }
+
+    /// How far along the track `value` is, from 0 to 1.
+    fn fraction(&self, value: f64) -> f64 {
+        if self.max > self.min {
+            ((value - self.min) / (self.max - self.min)).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+
+    /// The length of the track between the centers of the thumbs at either end.
+    fn travel(&self, size: Size) -> f64 {
+        (self.axis.major(size) - 2. * THUMB_RADIUS).max(0.)
+    }
+
+    fn cross_size(&self) -> f64 {
+        let ticks = if self.tick_every.is_some() {
+            TICK_GAP + TICK_LENGTH
+        } else {
+            0.
+        };
+        2. * THUMB_RADIUS + ticks
+    }
+
+    /// Where the center of a thumb at `value` sits.
+    fn thumb_center(&self, size: Size, value: f64) -> Point {
+        let along = THUMB_RADIUS + self.fraction(value) * self.travel(size);
+        let across = THUMB_RADIUS + (self.axis.minor(size) - self.cross_size()) / 2.;
+        match self.axis {
+            Axis::Horizontal => Point::new(along, across),
+            Axis::Vertical => Point::new(across, size.height - along),
+        }
+    }
+
+    fn value_at(&self, size: Size, pos: Point) -> f64 {
+        let along = match self.axis {
+            Axis::Horizontal => pos.x,
+            Axis::Vertical => size.height - pos.y,
+        };
+        let travel = self.travel(size);
+        let fraction = if travel > 0. {
+            ((along - THUMB_RADIUS) / travel).clamp(0., 1.)
+        } else {
+            0.
+        };
+        self.snap(self.min + fraction * (self.max - self.min))
+    }
+
+    /// The thumb a press at `value` grabs, the nearest one, or for thumbs on top of each
+    /// other the one on the side of the press.
+    fn nearest_thumb(thumbs: &[f64], value: f64) -> usize {
+        let mut nearest = 0;
+        for (index, &thumb) in thumbs.iter().enumerate().skip(1) {
+            let distance = (thumb - value).abs();
+            let best = (thumbs[nearest] - value).abs();
+            if distance < best || (distance == best && value > thumb) {
+                nearest = index;
+            }
+        }
+        nearest
+    }
+
+    fn move_thumb(&mut self, ctx: &mut EventCtx, data: &mut T, index: usize, value: f64) {
+        data.set_thumb(index, self.snap(value));
+        ctx.request_paint();
+    }
+
+    fn paint_tooltip(&self, ctx: &mut PaintCtx, thumb: Point, value: f64, env: &Env) {
+        let Some(format) = &self.format else {
+            return;
+        };
+        let font = theme::get(env, theme::FONT);
+        let layout = ctx
+            .text()
+            .new_text_layout(format(value))
+            .font(font.family.clone(), font.size)
+            .text_color(theme::get(env, theme::TEXT))
+            .build();
+        let Ok(layout) = layout.inspect_err(|err| warn!(%err, "slider tooltip layout")) else {
+            return;
+        };
+        let text = layout.size();
+        let bubble = Size::new(
+            text.width + 2. * TOOLTIP_PADDING,
+            text.height + 2. * TOOLTIP_PADDING,
+        );
+        let origin = match self.axis {
+            Axis::Horizontal => Point::new(
+                thumb.x - bubble.width / 2.,
+                thumb.y - THUMB_RADIUS - TICK_GAP - bubble.height,
+            ),
+            Axis::Vertical => Point::new(
+                thumb.x - THUMB_RADIUS - TICK_GAP - bubble.width,
+                thumb.y - bubble.height / 2.,
+            ),
+        };
+        let rect = Rect::from_origin_size(origin, bubble)
+            .to_rounded_rect(theme::get(env, theme::CORNER_RADIUS));
+        ctx.fill(rect, &theme::get(env, theme::POPUP_BACKGROUND));
+        ctx.stroke(
+            rect,
+            &theme::get(env, theme::BORDER),
+            theme::get(env, theme::BORDER_WIDTH),
+        );
+        ctx.draw_text(&layout, origin + (TOOLTIP_PADDING, TOOLTIP_PADDING));
+    }
+}
+
+impl<T: SliderValue> Default for Slider<T> {
+    fn default() -> Self {
+        Slider::new()
+    }
+}
+
+impl<T: SliderValue> Widget<T> for Slider<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, _env: &Env) {
+        if ctx.is_disabled() {
+            return;
+        }
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                ctx.set_active(true);
+                let value = self.value_at(ctx.size(), mouse.pos);
+                let index = Slider::<T>::nearest_thumb(&data.thumbs(), value);
+                self.dragging = Some(index);
+                self.current = index;
+                self.move_thumb(ctx, data, index, value);
+            }
+            Event::MouseMove(mouse) => {
+                if let Some(index) = self.dragging {
+                    let value = self.value_at(ctx.size(), mouse.pos);
+                    self.move_thumb(ctx, data, index, value);
+                }
+            }
+            Event::MouseUp(_) => {
+                self.dragging = None;
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) => {
+                let (step, page) = self.key_steps();
+                let index = self.current.min(data.thumbs().len() - 1);
+                let value = data.thumbs()[index];
+                let value = match &key.key {
+                    KbKey::ArrowRight | KbKey::ArrowUp => value + step,
+                    KbKey::ArrowLeft | KbKey::ArrowDown => value - step,
+                    KbKey::PageUp => value + page,
+                    KbKey::PageDown => value - page,
+                    KbKey::Home => self.min,
+                    KbKey::End => self.max,
+                    _ => return,
+                };
+                self.move_thumb(ctx, data, index, value);
+            }
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::FocusChanged(_)
+            | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) || ctx.env_changed() {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.debug_check("Slider");
+        let major = self.axis.major(bc.max());
+        let major = if major.is_finite() {
+            major
+        } else {
+            DEFAULT_LENGTH
+        };
+        let size = bc.constrain(self.axis.pack(major, self.cross_size()));
+        if self.format.is_some() {
+            let insets = match self.axis {
+                Axis::Horizontal => Insets::new(0., TOOLTIP_SPACE, 0., 0.),
+                Axis::Vertical => Insets::new(TOOLTIP_SPACE, 0., 0., 0.),
+            };
+            ctx.set_paint_insets(insets);
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        let disabled = ctx.is_disabled();
+        let thumbs = data.thumbs();
+        let border = theme::get(env, theme::BORDER);
+        let accent = theme::get(env, theme::ACCENT);
+        let border_width = theme::get(env, theme::BORDER_WIDTH);
+        let surface = if disabled {
+            theme::get(env, theme::SURFACE_DISABLED)
+        } else {
+            theme::get(env, theme::SURFACE)
+        };
+
+        let start = self.thumb_center(size, self.min);
+        let end = self.thumb_center(size, self.max);
+        let track = Rect::from_points(start, end)
+            .inset(TRACK_THICKNESS / 2.)
+            .to_rounded_rect(TRACK_THICKNESS / 2.);
+        ctx.fill(track, &surface);
+        ctx.stroke(track, &border, border_width);
+
+        // a single thumb fills from the minimum, two fill between themselves
+        let (low, high) = match thumbs.as_slice() {
+            [value] => (self.min, *value),
+            [first, .., last] => (*first, *last),
+            [] => (self.min, self.min),
+        };
+        if high > low {
+            let filled =
+                Rect::from_points(self.thumb_center(size, low), self.thumb_center(size, high))
+                    .inset(TRACK_THICKNESS / 2.)
+                    .to_rounded_rect(TRACK_THICKNESS / 2.);
+            let fill = if disabled {
+                theme::get(env, theme::TEXT_DISABLED)
+            } else {
+                accent
+            };
+            ctx.fill(filled, &fill);
+        }
+
+        if let Some(every) = self.tick_every {
+            let count = ((self.max - self.min) / every).floor() as usize;
+            for tick in 0..=count {
+                let center = self.thumb_center(size, self.min + tick as f64 * every);
+                let from = THUMB_RADIUS + TICK_GAP;
+                let to = from + TICK_LENGTH;
+                let line = match self.axis {
+                    Axis::Horizontal => Line::new(center + (0., from), center + (0., to)),
+                    Axis::Vertical => Line::new(center + (from, 0.), center + (to, 0.)),
+                };
+                ctx.stroke(line, &border, 1.);
+            }
+        }
+
+        for (index, &value) in thumbs.iter().enumerate() {
+            let center = self.thumb_center(size, value);
+            let dragged = self.dragging == Some(index);
+            let (stroke, width) = if dragged || (ctx.is_focused() && self.current == index) {
+                (&accent, theme::get(env, theme::ACTIVE_BORDER_WIDTH))
+            } else {
+                (&border, border_width)
+            };
+            let circle = Circle::new(center, THUMB_RADIUS - width / 2.);
+            ctx.fill(circle, &surface);
+            ctx.stroke(circle, stroke, width);
+        }
+
+        if let Some(index) = self.dragging
+            && let Some(&value) = thumbs.get(index)
+        {
+            self.paint_tooltip(ctx, self.thumb_center(size, value), value, env);
+        }
+    }
+}
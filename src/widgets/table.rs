@@ -0,0 +1,690 @@
+//! A table of rows with sortable, resizable columns.
+//!
+//! The rows live in the app data as a [`TableState`] along with the selection and the sort
+//! order, the rows below the header are a [`VirtualList`] that builds cells for the rows in
+//! view only. Clicking a header sorts by its column, Shift-clicking adds the column to the
+//! sort instead. Dragging the right edge of a header resizes its column.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use druid::im::{OrdSet, Vector};
+use druid::kurbo::{BezPath, Line};
+use druid::piet::{PietTextLayout, Text, TextLayout, TextLayoutBuilder};
+use druid::widget::prelude::*;
+use druid::{Cursor, KbKey, Lens, Modifiers, Point, Rect, WidgetExt, WidgetPod};
+use tracing::{debug, warn};
+
+use crate::theme;
+use crate::widgets::virtual_list::{VirtualList, VirtualListState};
+
+const HEADER_HEIGHT: f64 = 28.;
+const CELL_PADDING: f64 = 6.;
+/// Distance from the right edge of a header where a press resizes the column
+const RESIZE_GRIP: f64 = 4.;
+const SORT_ARROW: f64 = 4.;
+
+crate::selectors! {
+    /// The table asks itself for the selection to follow rows that moved, the data is
+    /// read only in `update`
+    pub(crate) MOVE_SELECTION: OrdSet<usize>,
+}
+
+/// A column the rows are sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub struct SortKey {
+    pub column: usize,
+    pub ascending: bool,
+}
+
+/// The rows of a [`Table`], which of them are selected and how they are sorted.
+#[derive(Debug, Clone, Data, Lens)]
+pub struct TableState<R: Clone> {
+    pub rows: Vector<R>,
+    /// Indices into `rows`, a [`Table`] moves them along when rows are inserted or removed
+    pub selected: OrdSet<usize>,
+    /// Most significant column first, rows comparing equal keep their order
+    pub sort: Vector<SortKey>,
+}
+
+impl<R: Clone> TableState<R> {
+    pub fn new(rows: Vector<R>) -> Self {
+        TableState {
+            rows,
+            selected: OrdSet::new(),
+            sort: Vector::new(),
+        }
+    }
+
+    pub fn selected_rows(&self) -> impl Iterator<Item = &R> + '_ {
+        self.selected
+            .iter()
+            .filter_map(|&index| self.rows.get(index))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    Single,
+    /// Ctrl-click toggles rows, Shift-click selects a range
+    Multi,
+}
+
+type CellFn<R> = Rc<dyn Fn() -> Box<dyn Widget<R>>>;
+type CompareFn<R> = Box<dyn Fn(&R, &R) -> Ordering>;
+
+/// How a [`Table`] shows one column of its rows.
+pub struct Column<R> {
+    header: String,
+    make_cell: CellFn<R>,
+    width: f64,
+    min_width: f64,
+    max_width: f64,
+    compare: Option<CompareFn<R>>,
+}
+
+impl<R: Data> Column<R> {
+    pub fn new<W: Widget<R> + 'static>(
+        header: impl Into<String>,
+        make_cell: impl Fn() -> W + 'static,
+        width: f64,
+    ) -> Self {
+        Column {
+            header: header.into(),
+            make_cell: Rc::new(move || make_cell().boxed()),
+            width,
+            min_width: 2. * CELL_PADDING + RESIZE_GRIP,
+            max_width: f64::INFINITY,
+            compare: None,
+        }
+    }
+
+    /// Limits how far the column can be resized.
+    pub fn with_bounds(mut self, min_width: f64, max_width: f64) -> Self {
+        self.min_width = min_width;
+        self.max_width = max_width.max(min_width);
+        self.width = self.width.clamp(self.min_width, self.max_width);
+        self
+    }
+
+    /// Makes the column sortable, ordering rows with `compare`.
+    pub fn sortable_by(mut self, compare: impl Fn(&R, &R) -> Ordering + 'static) -> Self {
+        self.compare = Some(Box::new(compare));
+        self
+    }
+
+    /// Makes the column sortable, ordering rows by `key`.
+    pub fn sortable_by_key<K: Ord>(self, key: impl Fn(&R) -> K + 'static) -> Self {
+        self.sortable_by(move |a, b| key(a).cmp(&key(b)))
+    }
+}
+
+struct Resize {
+    column: usize,
+    start_x: f64,
+    start_width: f64,
+}
+
+/// A row as the [`VirtualList`] under the header shows it.
+#[derive(Clone, Data)]
+struct Shown<R> {
+    /// Index into the data's rows
+    index: usize,
+    row: R,
+    selected: bool,
+}
+
+/// The cells of one row, side by side in their columns.
+struct RowCells<R> {
+    cells: Vec<WidgetPod<R, Box<dyn Widget<R>>>>,
+    widths: Rc<RefCell<Vec<f64>>>,
+}
+
+type Rows<R> = WidgetPod<VirtualListState<Shown<R>>, VirtualList<Shown<R>>>;
+
+pub struct Table<R: Data> {
+    columns: Vec<Column<R>>,
+    headers: Vec<Option<PietTextLayout>>,
+    row_height: f64,
+    selection: SelectionMode,
+    /// The rows below the header, rebuilt as columns are added
+    list: Rows<R>,
+    /// What `list` shows, kept in step with the data in `update`
+    shown: VirtualListState<Shown<R>>,
+    /// Column widths as of the last layout, shared with the rows
+    widths: Rc<RefCell<Vec<f64>>>,
+    /// Indices into the data's rows, in the order they are shown
+    order: Vec<usize>,
+    /// Where each of the data's rows is shown, the inverse of `order`
+    positions: Vec<usize>,
+    /// The row Shift-click and Shift+arrows extend the selection from
+    anchor: Option<usize>,
+    /// The row arrows move from, the last one clicked or moved to
+    cursor: Option<usize>,
+    resize: Option<Resize>,
+}
+
+impl<R: Data> Table<R> {
+    pub fn new(row_height: f64) -> Self {
+        let widths = Rc::default();
+        Table {
+            columns: Vec::new(),
+            headers: Vec::new(),
+            row_height,
+            selection: SelectionMode::Single,
+            list: Self::rows(&[], &widths, row_height),
+            shown: VirtualListState::new(Vector::new()),
+            widths,
+            order: Vec::new(),
+            positions: Vec::new(),
+            anchor: None,
+            cursor: None,
+            resize: None,
+        }
+    }
+
+    pub fn with_column(mut self, column: Column<R>) -> Self {
+        self.columns.push(column);
+        self.list = Self::rows(&self.columns, &self.widths, self.row_height);
+        self
+    }
+
+    pub fn with_selection(mut self, selection: SelectionMode) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    fn rows(columns: &[Column<R>], widths: &Rc<RefCell<Vec<f64>>>, row_height: f64) -> Rows<R> {
+        let make_cells: Vec<_> = columns
+            .iter()
+            .map(|column| column.make_cell.clone())
+            .collect();
+        let widths = widths.clone();
+        WidgetPod::new(VirtualList::new(
+            move || RowCells {
+                cells: make_cells
+                    .iter()
+                    .map(|make_cell| WidgetPod::new(make_cell()))
+                    .collect(),
+                widths: widths.clone(),
+            },
+            row_height,
+        ))
+    }
+
+    fn sort(&mut self, data: &TableState<R>) {
+        self.order = (0..data.rows.len()).collect();
+        let keys: Vec<_> = data
+            .sort
+            .iter()
+            .filter_map(|key| {
+                let compare = self.columns.get(key.column)?.compare.as_ref()?;
+                Some((compare, key.ascending))
+            })
+            .collect();
+        if keys.is_empty() {
+            return;
+        }
+        // `sort_by` is stable, rows comparing equal on every key keep their order
+        self.order.sort_by(|&a, &b| {
+            let (a, b) = (&data.rows[a], &data.rows[b]);
+            keys.iter()
+                .map(|(compare, ascending)| {
+                    let ordering = compare(a, b);
+                    if *ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        debug!(rows = self.order.len(), sort = ?data.sort, "table sorted");
+    }
+
+    /// Shows the rows in `order` again, from scratch.
+    fn show(&mut self, data: &TableState<R>) {
+        self.positions = vec![0; self.order.len()];
+        for (position, &index) in self.order.iter().enumerate() {
+            self.positions[index] = position;
+        }
+        self.shown.items = self
+            .order
+            .iter()
+            .map(|&index| Shown {
+                index,
+                row: data.rows[index].clone(),
+                selected: data.selected.contains(&index),
+            })
+            .collect();
+    }
+
+    /// Hands the shown rows the rows and selection that changed in place.
+    fn patch(&mut self, old_data: &TableState<R>, data: &TableState<R>) {
+        if !old_data.rows.same(&data.rows) {
+            let changed = old_data.rows.iter().zip(data.rows.iter()).enumerate();
+            for (index, (_, row)) in changed.filter(|(_, (old, new))| !old.same(new)) {
+                let position = self.positions[index];
+                if !self.shown.items[position].row.same(row) {
+                    self.shown.items[position].row = row.clone();
+                }
+            }
+        }
+        if !old_data.selected.same(&data.selected) {
+            for &index in old_data.selected.iter().chain(&data.selected) {
+                let Some(&position) = self.positions.get(index) else {
+                    continue;
+                };
+                let selected = data.selected.contains(&index);
+                if self.shown.items[position].selected != selected {
+                    self.shown.items[position].selected = selected;
+                }
+            }
+        }
+    }
+
+    /// Whether the rows have to be sorted again, rows edited in place only move when a
+    /// column they are sorted by changed.
+    fn needs_sort(&self, old_data: &TableState<R>, data: &TableState<R>) -> bool {
+        if !old_data.sort.same(&data.sort) || old_data.rows.len() != data.rows.len() {
+            return true;
+        }
+        if old_data.rows.same(&data.rows) {
+            return false;
+        }
+        let compares: Vec<_> = data
+            .sort
+            .iter()
+            .filter_map(|key| self.columns.get(key.column)?.compare.as_ref())
+            .collect();
+        old_data
+            .rows
+            .iter()
+            .zip(data.rows.iter())
+            .filter(|(old, new)| !old.same(new))
+            .any(|(old, new)| compares.iter().any(|compare| compare(old, new).is_ne()))
+    }
+
+    fn total_width(&self) -> f64 {
+        self.columns.iter().map(|column| column.width).sum()
+    }
+
+    /// The column whose header is at `x`, and whether `x` is on its resize grip.
+    fn column_at(&self, x: f64) -> Option<(usize, bool)> {
+        let mut right = 0.;
+        for (index, column) in self.columns.iter().enumerate() {
+            right += column.width;
+            if x < right + RESIZE_GRIP {
+                return Some((index, x > right - RESIZE_GRIP));
+            }
+        }
+        None
+    }
+
+    fn sort_by_column(&self, data: &mut TableState<R>, column: usize, mods: Modifiers) {
+        if self.columns[column].compare.is_none() {
+            return;
+        }
+        let existing = data.sort.iter().position(|key| key.column == column);
+        if mods.shift() {
+            match existing {
+                Some(index) => data.sort[index].ascending = !data.sort[index].ascending,
+                None => data.sort.push_back(SortKey {
+                    column,
+                    ascending: true,
+                }),
+            }
+        } else {
+            // clicking the only sorted column flips it, any other click starts over
+            let ascending =
+                !(existing == Some(0) && data.sort.len() == 1 && data.sort[0].ascending);
+            data.sort = Vector::unit(SortKey { column, ascending });
+        }
+    }
+
+    fn select(&mut self, data: &mut TableState<R>, position: usize, mods: Modifiers) {
+        let index = self.order[position];
+        let toggle = mods.ctrl() || mods.meta();
+        match self.selection {
+            SelectionMode::Multi if mods.shift() => {
+                let anchor = *self.anchor.get_or_insert(position);
+                let range = anchor.min(position)..=anchor.max(position);
+                data.selected = range.map(|position| self.order[position]).collect();
+            }
+            SelectionMode::Multi if toggle => {
+                if data.selected.remove(&index).is_none() {
+                    data.selected.insert(index);
+                }
+                self.anchor = Some(position);
+            }
+            _ => {
+                data.selected = OrdSet::unit(index);
+                self.anchor = Some(position);
+            }
+        }
+        self.cursor = Some(position);
+    }
+
+    /// Hands an event to the rows, returns the row the list selected with it.
+    fn event_rows(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TableState<R>,
+        env: &Env,
+    ) -> Option<usize> {
+        let mut shown = self.shown.clone();
+        // the list moves its selection from the cursor with the arrows, it doesn't keep one
+        let cursor = match event {
+            Event::KeyDown(_) => self.cursor,
+            _ => None,
+        };
+        shown.selected = cursor;
+        self.list.event(ctx, event, &mut shown, env);
+        let selected = shown
+            .selected
+            .take()
+            .filter(|&position| Some(position) != cursor);
+        if !shown.items.same(&self.shown.items) {
+            for (item, old) in shown.items.iter().zip(self.shown.items.iter()) {
+                if !item.row.same(&old.row) {
+                    data.rows.set(item.index, item.row.clone());
+                }
+            }
+        }
+        self.shown = shown;
+        selected
+    }
+}
+
+/// Finds the selected rows of `old_data` again in `data`, in order and by `Data::same`.
+/// Rows that went away drop out of the selection.
+fn moved_selection<R: Data>(old_data: &TableState<R>, data: &TableState<R>) -> OrdSet<usize> {
+    let mut next = 0;
+    let mut selected = OrdSet::new();
+    for &index in &old_data.selected {
+        let Some(row) = old_data.rows.get(index) else {
+            continue;
+        };
+        if let Some(found) = (next..data.rows.len()).find(|&new| data.rows[new].same(row)) {
+            selected.insert(found);
+            next = found + 1;
+        }
+    }
+    selected
+}
+
+impl<R: Data> Widget<TableState<R>> for Table<R> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut TableState<R>, env: &Env) {
+        crate::match_command!(event, ctx => {
+            Command(MOVE_SELECTION(selected)) => data.selected = selected.clone(),
+            _ => {}
+        });
+        if ctx.is_handled() {
+            return;
+        }
+
+        if let Some(resize) = &self.resize {
+            match event {
+                Event::MouseMove(mouse) => {
+                    let column = &mut self.columns[resize.column];
+                    column.width = (resize.start_width + mouse.pos.x - resize.start_x)
+                        .clamp(column.min_width, column.max_width);
+                    ctx.request_layout();
+                }
+                Event::MouseUp(_) => {
+                    self.resize = None;
+                    ctx.set_active(false);
+                }
+                _ => {}
+            }
+            ctx.set_handled();
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if mouse.pos.y < HEADER_HEIGHT => {
+                match self.column_at(mouse.pos.x) {
+                    Some((column, true)) => {
+                        self.resize = Some(Resize {
+                            column,
+                            start_x: mouse.pos.x,
+                            start_width: self.columns[column].width,
+                        });
+                        ctx.set_active(true);
+                    }
+                    Some((column, false)) => self.sort_by_column(data, column, mouse.mods),
+                    None => {}
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                let on_grip = mouse.pos.y < HEADER_HEIGHT
+                    && matches!(self.column_at(mouse.pos.x), Some((_, true)));
+                if on_grip {
+                    ctx.set_cursor(&Cursor::ResizeLeftRight);
+                } else {
+                    ctx.clear_cursor();
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(position) = self.event_rows(ctx, event, data, env) {
+            match event {
+                Event::MouseDown(mouse) => self.select(data, position, mouse.mods),
+                // Shift+arrows extend the selection like Shift-click
+                Event::KeyDown(key) => self.select(data, position, key.mods & Modifiers::SHIFT),
+                _ => {}
+            }
+        }
+        if ctx.is_handled() {
+            return;
+        }
+        if let Event::KeyDown(key) = event
+            && key.key == KbKey::Character("a".into())
+            && (key.mods.ctrl() || key.mods.meta())
+            && self.selection == SelectionMode::Multi
+        {
+            data.selected = (0..data.rows.len()).collect();
+            ctx.set_handled();
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &TableState<R>,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.sort(data);
+            self.show(data);
+        }
+        self.list.lifecycle(ctx, event, &self.shown, env);
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &TableState<R>,
+        data: &TableState<R>,
+        env: &Env,
+    ) {
+        if old_data.rows.len() != data.rows.len() {
+            self.anchor = None;
+            self.cursor = None;
+            // unless whoever changed the rows picked a new selection too
+            if old_data.selected.same(&data.selected) {
+                let selected = moved_selection(old_data, data);
+                if selected != data.selected {
+                    ctx.submit_command(MOVE_SELECTION.with(selected).to(ctx.widget_id()));
+                }
+            }
+        }
+        if self.needs_sort(old_data, data) {
+            self.sort(data);
+            self.show(data);
+        } else {
+            self.patch(old_data, data);
+        }
+        // the headers are laid out with the theme's font and text color
+        if ctx.env_changed() || !old_data.sort.same(&data.sort) {
+            ctx.request_layout();
+        }
+        self.list.update(ctx, &self.shown, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &TableState<R>,
+        env: &Env,
+    ) -> Size {
+        let font = theme::get(env, theme::FONT);
+        let text = theme::get(env, theme::TEXT);
+        self.headers = self
+            .columns
+            .iter()
+            .map(|column| {
+                let layout = ctx
+                    .text()
+                    .new_text_layout(column.header.clone())
+                    .font(font.family.clone(), font.size)
+                    .text_color(text)
+                    .build();
+                layout
+                    .inspect_err(|err| warn!(%err, header = column.header, "table header layout"))
+                    .ok()
+            })
+            .collect();
+        *self.widths.borrow_mut() = self.columns.iter().map(|column| column.width).collect();
+
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            self.total_width()
+        };
+        // the list picks its own height when the table isn't given one
+        let list_bc = if bc.is_height_bounded() {
+            let height = (bc.max().height - HEADER_HEIGHT).max(0.);
+            BoxConstraints::tight(Size::new(width, height))
+        } else {
+            BoxConstraints::new(Size::new(width, 0.), Size::new(width, f64::INFINITY))
+        };
+        let list_size = self.list.layout(ctx, &list_bc, &self.shown, env);
+        self.list.set_origin(ctx, Point::new(0., HEADER_HEIGHT));
+        bc.constrain(Size::new(width, HEADER_HEIGHT + list_size.height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &TableState<R>, env: &Env) {
+        let size = ctx.size();
+        let border = theme::get(env, theme::BORDER);
+        let surface = theme::get(env, theme::SURFACE);
+        let text = theme::get(env, theme::TEXT);
+
+        self.list.paint(ctx, &self.shown, env);
+
+        let header = Rect::new(0., 0., size.width, HEADER_HEIGHT);
+        ctx.fill(header, &surface);
+        ctx.stroke(
+            Line::new((0., HEADER_HEIGHT - 0.5), (size.width, HEADER_HEIGHT - 0.5)),
+            &border,
+            1.,
+        );
+        let mut x = 0.;
+        for (index, (column, label)) in self.columns.iter().zip(&self.headers).enumerate() {
+            let right = x + column.width;
+            let sorted = data.sort.iter().find(|key| key.column == index);
+            ctx.with_save(|ctx| {
+                ctx.clip(Rect::new(x, 0., right, HEADER_HEIGHT));
+                let label_width = label.as_ref().map_or(0., |label| label.size().width);
+                if let Some(label) = label {
+                    let y = (HEADER_HEIGHT - label.size().height) / 2.;
+                    ctx.draw_text(label, (x + CELL_PADDING, y));
+                }
+                if let Some(key) = sorted {
+                    let cx = x + CELL_PADDING + label_width + CELL_PADDING + SORT_ARROW;
+                    let cy = HEADER_HEIGHT / 2.;
+                    let tip = if key.ascending {
+                        -SORT_ARROW
+                    } else {
+                        SORT_ARROW
+                    } / 2.;
+                    let mut arrow = BezPath::new();
+                    arrow.move_to((cx - SORT_ARROW, cy - tip));
+                    arrow.line_to((cx + SORT_ARROW, cy - tip));
+                    arrow.line_to((cx, cy + tip));
+                    arrow.close_path();
+                    ctx.fill(arrow, &text);
+                }
+            });
+            ctx.stroke(
+                Line::new((right - 0.5, 0.), (right - 0.5, HEADER_HEIGHT)),
+                &border,
+                1.,
+            );
+            x = right;
+        }
+    }
+}
+
+impl<R: Data> Widget<Shown<R>> for RowCells<R> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Shown<R>, env: &Env) {
+        for cell in &mut self.cells {
+            cell.event(ctx, event, &mut data.row, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Shown<R>, env: &Env) {
+        for cell in &mut self.cells {
+            cell.lifecycle(ctx, event, &data.row, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Shown<R>, data: &Shown<R>, env: &Env) {
+        if old_data.selected != data.selected {
+            ctx.request_paint();
+        }
+        for cell in &mut self.cells {
+            cell.update(ctx, &data.row, env);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Shown<R>,
+        env: &Env,
+    ) -> Size {
+        let size = bc.max();
+        let mut x = 0.;
+        for (cell, &width) in self.cells.iter_mut().zip(self.widths.borrow().iter()) {
+            let cell_size = Size::new(width, size.height);
+            cell.layout(ctx, &BoxConstraints::tight(cell_size), &data.row, env);
+            cell.set_origin(ctx, Point::new(x, 0.));
+            x += width;
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Shown<R>, env: &Env) {
+        if data.selected {
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &theme::get(env, theme::ACCENT).with_alpha(0.35));
+        }
+        for cell in &mut self.cells {
+            // cells don't draw past their column
+            ctx.with_save(|ctx| {
+                ctx.clip(cell.layout_rect());
+                cell.paint(ctx, &data.row, env);
+            });
+        }
+    }
+}
@@ -0,0 +1,560 @@
+//! A tab bar over the content of the selected tab.
+//!
+//! The tabs live in the app data as a [`TabsState`], so closing and reordering them is
+//! visible to the rest of the app. Tabs that don't fit scroll with the mouse wheel and
+//! are listed in a [`Dropdown`] at the end of the bar. Ctrl+Tab and Ctrl+Shift+Tab switch
+//! tabs while anything in the widget has focus.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use druid::im::Vector;
+use druid::kurbo::Line;
+use druid::piet::{PietTextLayout, Text, TextLayout, TextLayoutBuilder};
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label};
+use druid::{Affine, KbKey, Lens, Point, Rect, WidgetExt, WidgetPod};
+use tracing::{debug, warn};
+
+use crate::theme;
+use crate::widgets::dropdown::{DROPDOWN_SHOW, Dropdown};
+use crate::widgets::popup::POPUP_DISMISS;
+
+const BAR_HEIGHT: f64 = 30.;
+const MIN_TAB_WIDTH: f64 = 80.;
+const TAB_PADDING: f64 = 10.;
+const CLOSE_SIZE: f64 = 8.;
+/// Distance the pointer travels before a press on a tab turns into a drag
+const DRAG_THRESHOLD: f64 = 4.;
+const UNDERLINE: f64 = 2.;
+
+/// The tabs and which one is selected, bound to a [`Tabs`] widget.
+#[derive(Debug, Clone, Data, Lens)]
+pub struct TabsState<T: Clone> {
+    pub tabs: Vector<T>,
+    pub selected: usize,
+}
+
+impl<T: Clone> TabsState<T> {
+    pub fn new(tabs: Vector<T>) -> Self {
+        TabsState { tabs, selected: 0 }
+    }
+
+    pub fn selected_tab(&self) -> Option<&T> {
+        self.tabs.get(self.selected)
+    }
+
+    /// Removes the tab at `index`, keeping the same tab selected when it can.
+    pub fn close(&mut self, index: usize) -> Option<T> {
+        if index >= self.tabs.len() {
+            return None;
+        }
+        let tab = self.tabs.remove(index);
+        if index < self.selected || self.selected >= self.tabs.len() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        Some(tab)
+    }
+
+    /// Moves the tab at `from` to `to`, the selection follows the tab it was on.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.tabs.len() || to >= self.tabs.len() {
+            return;
+        }
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        if self.selected == from {
+            self.selected = to;
+        } else if from < self.selected && self.selected <= to {
+            self.selected -= 1;
+        } else if to <= self.selected && self.selected < from {
+            self.selected += 1;
+        }
+    }
+}
+
+type LabelFn<T> = Box<dyn Fn(&T) -> String>;
+type ContentFn<T> = Box<dyn Fn(&T, &Env) -> Box<dyn Widget<T>>>;
+
+struct Drag {
+    index: usize,
+    /// Pointer position when the tab was pressed, in bar coordinates
+    start: f64,
+    /// Where on the tab the pointer holds it
+    grab: f64,
+    /// Pointer position, in bar coordinates
+    pointer: f64,
+    dragging: bool,
+}
+
+pub struct Tabs<T: Clone> {
+    label: LabelFn<T>,
+    make_content: ContentFn<T>,
+    closable: bool,
+    content: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    overflow: WidgetPod<TabsState<T>, Box<dyn Widget<TabsState<T>>>>,
+    /// Tabs that are not entirely in view, listed by the overflow dropdown
+    hidden: Rc<RefCell<Vec<usize>>>,
+    /// `None` for labels that failed to lay out, their tabs keep the minimum width
+    labels: Vec<Option<PietTextLayout>>,
+    /// Tab rects in bar coordinates, before scrolling
+    rects: Vec<Rect>,
+    /// Width of the bar left for tabs, the overflow button takes the rest
+    visible: f64,
+    scroll: f64,
+    reveal_selected: bool,
+    hot: Option<usize>,
+    hot_close: bool,
+    pressed_close: Option<usize>,
+    drag: Option<Drag>,
+}
+
+impl<T: Data> Tabs<T> {
+    /// Names every tab with `label` and shows `make_content` for the selected one.
+    pub fn new<W: Widget<T> + 'static>(
+        label: impl Fn(&T) -> String + 'static,
+        make_content: impl Fn(&T, &Env) -> W + 'static,
+    ) -> Self {
+        let label: Rc<dyn Fn(&T) -> String> = Rc::new(label);
+        let hidden = Rc::new(RefCell::new(Vec::new()));
+        let overflow = {
+            let label = label.clone();
+            let hidden = hidden.clone();
+            let button = Button::new("▾")
+                .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(DROPDOWN_SHOW));
+            Dropdown::new(button, move |data: &TabsState<T>, _| {
+                hidden_list(data, &hidden.borrow(), &*label)
+            })
+        };
+        Tabs {
+            label: Box::new(move |tab| label(tab)),
+            make_content: Box::new(move |tab, env| make_content(tab, env).boxed()),
+            closable: false,
+            content: None,
+            overflow: WidgetPod::new(overflow.boxed()),
+            hidden,
+            labels: Vec::new(),
+            rects: Vec::new(),
+            visible: 0.,
+            scroll: 0.,
+            reveal_selected: true,
+            hot: None,
+            hot_close: false,
+            pressed_close: None,
+            drag: None,
+        }
+    }
+
+    /// Gives every tab a button closing it.
+    pub fn closable(mut self) -> Self {
+        self.closable = true;
+        self
+    }
+
+    fn tab_at(&self, x: f64) -> Option<usize> {
+        if x < 0. || x >= self.visible {
+            return None;
+        }
+        let x = x + self.scroll;
+        self.rects
+            .iter()
+            .position(|rect| rect.x0 <= x && x < rect.x1)
+    }
+
+    fn close_rect(&self, index: usize) -> Option<Rect> {
+        let rect = self.rects.get(index)?;
+        self.closable.then(|| {
+            let x1 = rect.x1 - TAB_PADDING;
+            let y = rect.center().y;
+            Rect::new(
+                x1 - CLOSE_SIZE,
+                y - CLOSE_SIZE / 2.,
+                x1,
+                y + CLOSE_SIZE / 2.,
+            )
+        })
+    }
+
+    fn on_close(&self, index: usize, pos: Point) -> bool {
+        // a little slack, the glyph is small
+        self.close_rect(index)
+            .is_some_and(|rect| rect.inflate(3., 3.).contains(pos + (self.scroll, 0.)))
+    }
+
+    fn build_content(&mut self, data: &TabsState<T>, env: &Env) {
+        self.content = data
+            .selected_tab()
+            .map(|tab| WidgetPod::new((self.make_content)(tab, env)));
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, data: &mut TabsState<T>, index: usize) {
+        if data.selected != index {
+            debug!(index, "tab selected");
+            data.selected = index;
+        }
+        self.reveal_selected = true;
+        ctx.request_layout();
+    }
+
+    fn drag_to(&mut self, data: &mut TabsState<T>, pointer: f64) {
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+        drag.pointer = pointer;
+        if !drag.dragging && (pointer - drag.start).abs() < DRAG_THRESHOLD {
+            return;
+        }
+        drag.dragging = true;
+
+        // swap with a neighbour once the dragged tab's middle passes the neighbour's
+        let Some(width) = self.rects.get(drag.index).map(Rect::width) else {
+            return;
+        };
+        let middle = pointer - drag.grab + width / 2.;
+        let mut to = drag.index;
+        while to + 1 < self.rects.len() && middle > self.rects[to + 1].center().x {
+            to += 1;
+        }
+        while to > 0 && middle < self.rects[to - 1].center().x {
+            to -= 1;
+        }
+        if to != drag.index {
+            debug!(from = drag.index, to, "tab moved");
+            data.move_tab(drag.index, to);
+            let rect = self.rects.remove(drag.index);
+            self.rects.insert(to, rect);
+            let label = self.labels.remove(drag.index);
+            self.labels.insert(to, label);
+            drag.index = to;
+            let mut x = 0.;
+            for rect in &mut self.rects {
+                *rect = rect.with_origin((x, 0.));
+                x += rect.width();
+            }
+        }
+    }
+}
+
+fn hidden_list<T: Data>(
+    data: &TabsState<T>,
+    hidden: &[usize],
+    label: &dyn Fn(&T) -> String,
+) -> impl Widget<TabsState<T>> + use<T> {
+    let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for &index in hidden {
+        let Some(tab) = data.tabs.get(index) else {
+            continue;
+        };
+        list.add_child(Label::new(label(tab)).padding((0., 2.)).on_click(
+            move |ctx, data: &mut TabsState<T>, _| {
+                data.selected = index;
+                ctx.submit_command(POPUP_DISMISS.to(ctx.window_id()));
+            },
+        ));
+    }
+    list
+}
+
+impl<T: Data> Widget<TabsState<T>> for Tabs<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut TabsState<T>, env: &Env) {
+        match event {
+            Event::KeyDown(key) if key.key == KbKey::Tab && key.mods.ctrl() => {
+                let len = data.tabs.len();
+                if len > 0 {
+                    let next = if key.mods.shift() {
+                        (data.selected + len - 1) % len
+                    } else {
+                        (data.selected + 1) % len
+                    };
+                    self.select(ctx, data, next);
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseDown(mouse) if mouse.pos.y < BAR_HEIGHT => {
+                if let Some(index) = self.tab_at(mouse.pos.x) {
+                    if self.on_close(index, mouse.pos) {
+                        self.pressed_close = Some(index);
+                    } else {
+                        self.select(ctx, data, index);
+                        let bar_x = mouse.pos.x + self.scroll;
+                        self.drag = Some(Drag {
+                            index,
+                            start: bar_x,
+                            grab: bar_x - self.rects[index].x0,
+                            pointer: bar_x,
+                            dragging: false,
+                        });
+                    }
+                    ctx.set_active(true);
+                    ctx.request_focus();
+                    ctx.request_paint();
+                    return;
+                }
+            }
+            Event::MouseMove(mouse) => {
+                if ctx.is_active() {
+                    self.drag_to(data, mouse.pos.x + self.scroll);
+                    ctx.request_paint();
+                    return;
+                }
+                let hot = if mouse.pos.y < BAR_HEIGHT {
+                    self.tab_at(mouse.pos.x)
+                } else {
+                    None
+                };
+                let hot_close = hot.is_some_and(|index| self.on_close(index, mouse.pos));
+                if hot != self.hot || hot_close != self.hot_close {
+                    self.hot = hot;
+                    self.hot_close = hot_close;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                if let Some(index) = self.pressed_close.take()
+                    && self.on_close(index, mouse.pos)
+                {
+                    debug!(index, "tab closed");
+                    data.close(index);
+                    self.hot = None;
+                }
+                self.drag = None;
+                ctx.request_layout();
+                return;
+            }
+            Event::Wheel(mouse) if mouse.pos.y < BAR_HEIGHT => {
+                let delta = if mouse.wheel_delta.x != 0. {
+                    mouse.wheel_delta.x
+                } else {
+                    mouse.wheel_delta.y
+                };
+                self.scroll += delta;
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+
+        self.overflow.event(ctx, event, data, env);
+        if let Some(content) = &mut self.content
+            && let Some(tab) = data.tabs.get(data.selected)
+        {
+            let mut tab = tab.clone();
+            content.event(ctx, event, &mut tab, env);
+            if !tab.same(&data.tabs[data.selected]) {
+                data.tabs.set(data.selected, tab);
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &TabsState<T>,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.build_content(data, env);
+        }
+        if let LifeCycle::HotChanged(false) = event {
+            self.hot = None;
+            self.hot_close = false;
+            ctx.request_paint();
+        }
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+        self.overflow.lifecycle(ctx, event, data, env);
+        if let Some(content) = &mut self.content
+            && let Some(tab) = data.selected_tab()
+        {
+            content.lifecycle(ctx, event, tab, env);
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &TabsState<T>,
+        data: &TabsState<T>,
+        env: &Env,
+    ) {
+        // the labels are laid out with the theme's font and text color
+        if !old_data.tabs.same(&data.tabs) || ctx.env_changed() {
+            ctx.request_layout();
+        }
+        // dragging keeps the selected tab, only its index moves
+        let dragging = self.drag.as_ref().is_some_and(|drag| drag.dragging);
+        let switched = old_data.selected != data.selected && !dragging;
+        if old_data.tabs.len() != data.tabs.len() {
+            // tabs added or closed elsewhere leave the dragged index pointing at another tab
+            self.drag = None;
+            self.pressed_close = None;
+        }
+        if switched || old_data.tabs.len() != data.tabs.len() {
+            self.build_content(data, env);
+            self.reveal_selected = true;
+            ctx.children_changed();
+        } else if let Some(content) = &mut self.content
+            && let Some(tab) = data.selected_tab()
+        {
+            content.update(ctx, tab, env);
+        }
+        self.overflow.update(ctx, data, env);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &TabsState<T>,
+        env: &Env,
+    ) -> Size {
+        let font = theme::get(env, theme::FONT);
+        let text = theme::get(env, theme::TEXT);
+        let close = if self.closable {
+            CLOSE_SIZE + TAB_PADDING / 2.
+        } else {
+            0.
+        };
+
+        if self.drag.is_none() {
+            self.labels.clear();
+            self.rects.clear();
+            let mut x = 0.;
+            for tab in &data.tabs {
+                let label = ctx
+                    .text()
+                    .new_text_layout((self.label)(tab))
+                    .font(font.family.clone(), font.size)
+                    .text_color(text)
+                    .build()
+                    .inspect_err(|err| warn!(%err, "tab label layout"))
+                    .ok();
+                let label_width = label.as_ref().map_or(0., |label| label.size().width);
+                let width = (label_width + 2. * TAB_PADDING + close).max(MIN_TAB_WIDTH);
+                self.rects.push(Rect::new(x, 0., x + width, BAR_HEIGHT));
+                self.labels.push(label);
+                x += width;
+            }
+        }
+        let total = self.rects.last().map_or(0., |rect| rect.x1);
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            total
+        };
+
+        // the overflow button only shows when the tabs don't fit
+        let button_bc = BoxConstraints::new(Size::ZERO, Size::new(width, BAR_HEIGHT));
+        let button = self.overflow.layout(ctx, &button_bc, data, env);
+        let overflowing = total > width;
+        self.visible = if overflowing {
+            width - button.width
+        } else {
+            width
+        };
+        let button_x = if overflowing { self.visible } else { width };
+        self.overflow
+            .set_origin(ctx, Point::new(button_x, (BAR_HEIGHT - button.height) / 2.));
+
+        if self.reveal_selected
+            && let Some(rect) = self.rects.get(data.selected)
+        {
+            if rect.x0 < self.scroll {
+                self.scroll = rect.x0;
+            } else if rect.x1 > self.scroll + self.visible {
+                self.scroll = rect.x1 - self.visible;
+            }
+        }
+        self.reveal_selected = false;
+        self.scroll = self.scroll.min(total - self.visible).max(0.);
+
+        let hidden = self.rects.iter().enumerate().filter_map(|(index, rect)| {
+            let shown = rect.x0 >= self.scroll && rect.x1 <= self.scroll + self.visible;
+            (!shown).then_some(index)
+        });
+        *self.hidden.borrow_mut() = hidden.collect();
+
+        let mut content_height = 0.;
+        if let Some(content) = &mut self.content
+            && let Some(tab) = data.selected_tab()
+        {
+            let max = Size::new(width, (bc.max().height - BAR_HEIGHT).max(0.));
+            let content_bc = BoxConstraints::new(Size::ZERO, max);
+            content_height = content.layout(ctx, &content_bc, tab, env).height;
+            content.set_origin(ctx, Point::new(0., BAR_HEIGHT));
+        }
+        let height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            BAR_HEIGHT + content_height
+        };
+        bc.constrain(Size::new(width, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &TabsState<T>, env: &Env) {
+        let size = ctx.size();
+        let border = theme::get(env, theme::BORDER);
+        let accent = theme::get(env, theme::ACCENT);
+        let surface = theme::get(env, theme::SURFACE);
+
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::new(0., 0., self.visible, BAR_HEIGHT));
+            ctx.transform(Affine::translate((-self.scroll, 0.)));
+
+            let dragged = self.drag.as_ref().filter(|drag| drag.dragging);
+            for (index, rect) in self.rects.iter().enumerate() {
+                let mut rect = *rect;
+                if let Some(drag) = dragged
+                    && drag.index == index
+                {
+                    // the dragged tab follows the pointer, its slot stays where it is
+                    rect = rect.with_origin((drag.pointer - drag.grab, 0.));
+                }
+                if index == data.selected {
+                    ctx.fill(rect, &surface);
+                    let underline = Rect::new(rect.x0, rect.y1 - UNDERLINE, rect.x1, rect.y1);
+                    ctx.fill(underline, &accent);
+                } else if self.hot == Some(index) {
+                    ctx.fill(rect, &surface.with_alpha(0.5));
+                }
+                if let Some(label) = self.labels.get(index).and_then(Option::as_ref) {
+                    let y = (BAR_HEIGHT - label.size().height) / 2.;
+                    ctx.draw_text(label, (rect.x0 + TAB_PADDING, y));
+                }
+                if let Some(close) = self.close_rect(index) {
+                    let close =
+                        close.with_origin((close.x0 + rect.x0 - self.rects[index].x0, close.y0));
+                    let hot = self.hot == Some(index) && self.hot_close;
+                    let color = if hot { accent } else { border };
+                    ctx.stroke(
+                        Line::new((close.x0, close.y0), (close.x1, close.y1)),
+                        &color,
+                        1.5,
+                    );
+                    ctx.stroke(
+                        Line::new((close.x0, close.y1), (close.x1, close.y0)),
+                        &color,
+                        1.5,
+                    );
+                }
+            }
+        });
+
+        let line = Line::new((0., BAR_HEIGHT - 0.5), (size.width, BAR_HEIGHT - 0.5));
+        ctx.stroke(line, &border, 1.);
+
+        if self.visible < size.width {
+            self.overflow.paint(ctx, data, env);
+        }
+        if let Some(content) = &mut self.content
+            && let Some(tab) = data.selected_tab()
+        {
+            content.paint(ctx, tab, env);
+        }
+    }
+}
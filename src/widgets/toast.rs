@@ -0,0 +1,384 @@
+//! Short-lived notifications stacked in a corner of the window.
+//!
+//! Wrap the root of a window in a [`ToastHost`], then submit [`SHOW_TOAST`] from
+//! anywhere in it:
+//!
+//! ```ignore
+//! ctx.submit_command(SHOW_TOAST.with(
+//!     Toast::success("Saved").with_action("Undo", UNDO_SAVE.with(path)),
+//! ));
+//! ```
+//!
+//! A toast goes away on its own after its timeout, which doesn't run while the pointer is
+//! over it. Clicking its action submits the action's command and closes the toast.
+
+use std::time::{Duration, Instant};
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, LineBreaking};
+use druid::{Color, Command, Key, Point, TimerToken, WidgetExt, WidgetPod};
+use tracing::debug;
+
+use crate::animation::Animated;
+use crate::theme;
+
+const WIDTH: f64 = 320.;
+/// Distance from the window edges
+const MARGIN: f64 = 16.;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(4);
+
+crate::selectors! {
+    /// Shows a toast in the window's [`ToastHost`]
+    SHOW_TOAST: Toast,
+    /// Sent up by a toast's close and action buttons, with the toast's id
+    pub(crate) CLOSE_TOAST: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn color(self) -> Key<Color> {
+        match self {
+            Severity::Info => theme::ACCENT,
+            Severity::Success => theme::SUCCESS,
+            Severity::Warning => theme::WARNING,
+            Severity::Error => theme::ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub severity: Severity,
+    /// Label of the action button, and the command it submits
+    pub action: Option<(String, Command)>,
+    /// `None` keeps the toast until it is closed
+    pub timeout: Option<Duration>,
+}
+
+impl Toast {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Toast {
+        Toast {
+            message: message.into(),
+            severity,
+            action: None,
+            timeout: Some(DEFAULT_TIMEOUT),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Toast {
+        Toast::new(Severity::Info, message)
+    }
+
+    pub fn success(message: impl Into<String>) -> Toast {
+        Toast::new(Severity::Success, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Toast {
+        Toast::new(Severity::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Toast {
+        Toast::new(Severity::Error, message)
+    }
+
+    pub fn with_action(mut self, label: impl Into<String>, command: impl Into<Command>) -> Toast {
+        self.action = Some((label.into(), command.into()));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Toast {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Keeps the toast until it is closed or its action clicked.
+    pub fn sticky(mut self) -> Toast {
+        self.timeout = None;
+        self
+    }
+}
+
+/// The corner of the window toasts stack in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+struct Entry {
+    id: u64,
+    view: WidgetPod<(), Box<dyn Widget<()>>>,
+    /// Slides in from the edge and back out, 0 is hidden
+    shown: Animated<f64>,
+    leaving: bool,
+    /// Time left before the toast closes on its own
+    remaining: Option<Duration>,
+    timer: TimerToken,
+    started: Instant,
+    hovered: bool,
+}
+
+impl Entry {
+    fn start_timer(&mut self, ctx: &mut EventCtx) {
+        if let Some(remaining) = self.remaining {
+            self.timer = ctx.request_timer(remaining);
+            self.started = Instant::now();
+        }
+    }
+
+    fn pause_timer(&mut self) {
+        if let Some(remaining) = &mut self.remaining
+            && self.timer != TimerToken::INVALID
+        {
+            *remaining = remaining.saturating_sub(self.started.elapsed());
+            self.timer = TimerToken::INVALID;
+        }
+    }
+}
+
+/// Shows the toasts submitted from anywhere inside `child` on top of it.
+pub struct ToastHost<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    toasts: Vec<Entry>,
+    corner: ToastCorner,
+    limit: usize,
+    next_id: u64,
+}
+
+impl<T: Data> ToastHost<T> {
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        ToastHost {
+            child: WidgetPod::new(child.boxed()),
+            toasts: Vec::new(),
+            corner: ToastCorner::BottomRight,
+            limit: 4,
+            next_id: 0,
+        }
+    }
+
+    pub fn with_corner(mut self, corner: ToastCorner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// How many toasts show at once, the oldest ones close to make room.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, toast: &Toast) {
+        let id = self.next_id;
+        self.next_id += 1;
+        debug!(id, severity = ?toast.severity, message = %toast.message, "toast shown");
+
+        let mut entry = Entry {
+            id,
+            view: WidgetPod::new(toast_view(id, toast).boxed()),
+            shown: Animated::new(0.),
+            leaving: false,
+            remaining: toast.timeout,
+            timer: TimerToken::INVALID,
+            started: Instant::now(),
+            hovered: false,
+        };
+        entry.start_timer(ctx);
+        if entry.shown.animate_to(1.) {
+            ctx.request_anim_frame();
+        }
+        self.toasts.push(entry);
+        ctx.children_changed();
+
+        let shown = self.toasts.iter().filter(|entry| !entry.leaving).count();
+        for _ in self.limit..shown {
+            if let Some(oldest) = self.toasts.iter().find(|entry| !entry.leaving) {
+                let id = oldest.id;
+                self.close(ctx, id);
+            }
+        }
+    }
+
+    fn close(&mut self, ctx: &mut EventCtx, id: u64) {
+        let Some(entry) = self.toasts.iter_mut().find(|entry| entry.id == id) else {
+            return;
+        };
+        if entry.leaving {
+            return;
+        }
+        debug!(id, "toast closed");
+        entry.leaving = true;
+        entry.timer = TimerToken::INVALID;
+        if entry.shown.animate_to(0.) {
+            ctx.request_anim_frame();
+        } else {
+            self.toasts.retain(|entry| entry.id != id);
+            ctx.children_changed();
+        }
+        ctx.request_layout();
+    }
+
+    fn over_toast(&self, pos: Point) -> bool {
+        self.toasts
+            .iter()
+            .any(|entry| entry.view.layout_rect().contains(pos))
+    }
+}
+
+fn toast_view(id: u64, toast: &Toast) -> impl Widget<()> + use<> {
+    let mut row = Flex::row()
+        // the message takes up the room the buttons leave, pushing them to the right end
+        .with_flex_child(
+            Label::new(toast.message.clone())
+                .with_line_break_mode(LineBreaking::WordWrap)
+                .expand_width(),
+            1.,
+        )
+        .cross_axis_alignment(CrossAxisAlignment::Center);
+    if let Some((label, command)) = toast.action.clone() {
+        row.add_default_spacer();
+        row.add_child(Button::new(label).on_click(move |ctx, _, _| {
+            ctx.submit_command(command.clone());
+            ctx.submit_notification(CLOSE_TOAST.with(id));
+        }));
+    }
+    row.add_default_spacer();
+    row.add_child(
+        Label::new("✕")
+            .with_text_size(11.)
+            .on_click(move |ctx, _, _| ctx.submit_notification(CLOSE_TOAST.with(id))),
+    );
+
+    row.padding(theme::POPUP_INSETS)
+        .background(theme::POPUP_BACKGROUND)
+        .rounded(theme::CORNER_RADIUS)
+        .border(toast.severity.color(), 1.)
+        .env_scope(|env, _| theme::complete(env))
+}
+
+impl<T: Data> Widget<T> for ToastHost<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        crate::match_command!(event, ctx => {
+            Command(SHOW_TOAST(toast)) => self.show(ctx, toast),
+            Notification(CLOSE_TOAST(id)) => self.close(ctx, *id),
+            _ => {}
+        });
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::AnimFrame(nanos) => {
+                let mut running = false;
+                for entry in &mut self.toasts {
+                    running |= entry.shown.advance(*nanos);
+                }
+                let before = self.toasts.len();
+                self.toasts
+                    .retain(|entry| !entry.leaving || entry.shown.is_running());
+                if self.toasts.len() != before {
+                    ctx.children_changed();
+                }
+                if running {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+            }
+            Event::Timer(token) => {
+                if let Some(entry) = self.toasts.iter().find(|entry| entry.timer == *token) {
+                    let id = entry.id;
+                    self.close(ctx, id);
+                    return;
+                }
+            }
+            _ => {}
+        }
+
+        for entry in &mut self.toasts {
+            entry.view.event(ctx, event, &mut (), env);
+            // the timeout waits while the pointer rests on the toast
+            if entry.view.is_hot() != entry.hovered && !entry.leaving {
+                entry.hovered = entry.view.is_hot();
+                if entry.hovered {
+                    entry.pause_timer();
+                } else {
+                    entry.start_timer(ctx);
+                }
+            }
+        }
+        if ctx.is_handled() {
+            return;
+        }
+        // toasts sit on top, clicks on them don't reach what's below
+        let blocked = match event {
+            Event::MouseDown(ev) | Event::MouseUp(ev) | Event::Wheel(ev) => self.over_toast(ev.pos),
+            _ => false,
+        };
+        if !blocked {
+            self.child.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+        for entry in &mut self.toasts {
+            entry.view.lifecycle(ctx, event, &(), env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        for entry in &mut self.toasts {
+            entry.view.update(ctx, &(), env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+
+        let width = WIDTH.min(size.width - 2. * MARGIN).max(0.);
+        let toast_bc = BoxConstraints::new(Size::new(width, 0.), Size::new(width, f64::INFINITY));
+        let spacing = theme::get(env, theme::SPACING);
+        let top = matches!(self.corner, ToastCorner::TopLeft | ToastCorner::TopRight);
+        let left = matches!(self.corner, ToastCorner::TopLeft | ToastCorner::BottomLeft);
+
+        // newest toasts sit in the corner, older ones move away from it
+        let mut offset = MARGIN;
+        for entry in self.toasts.iter_mut().rev() {
+            let shown = entry.shown.value();
+            let toast = entry.view.layout(ctx, &toast_bc, &(), env);
+            let slide = (1. - shown) * (toast.width + MARGIN);
+            let x = if left {
+                MARGIN - slide
+            } else {
+                size.width - MARGIN - toast.width + slide
+            };
+            let y = if top {
+                offset
+            } else {
+                size.height - offset - toast.height
+            };
+            entry.view.set_origin(ctx, Point::new(x, y));
+            // leaving toasts give their space back as they slide out
+            offset += (toast.height + spacing) * shown;
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        for entry in &mut self.toasts {
+            entry.view.paint(ctx, &(), env);
+        }
+    }
+}
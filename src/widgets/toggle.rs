@@ -0,0 +1,214 @@
+//! An on/off switch bound to a `bool`, drawn with the same theme keys as `AdvancedButton`.
+//!
+//! The thumb slides across a track that fills with the accent color as it goes. The
+//! switch takes focus when pressed, and Space or Enter flip it while it has focus.
+
+use druid::kurbo::Circle;
+use druid::widget::prelude::*;
+use druid::widget::{Label, LabelText};
+use druid::{Affine, Color, KbKey, Point, Rect};
+
+use crate::animation::{Animated, Tween};
+use crate::theme;
+
+const TRACK: Size = Size::new(36., 20.);
+/// Gap between the thumb and the edge of the track
+const THUMB_INSET: f64 = 3.;
+/// Distance from the track to its focus ring
+const FOCUS_GAP: f64 = 2.;
+
+/// Which side of the switch its label goes on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelPlacement {
+    Before,
+    #[default]
+    After,
+}
+
+pub struct Toggle {
+    label: Option<Label<bool>>,
+    label_size: Size,
+    placement: LabelPlacement,
+    /// 0 when off, 1 when on
+    thumb: Animated<f64>,
+}
+
+impl Toggle {
+    pub fn new() -> Self {
+        Toggle {
+            label: None,
+            label_size: Size::ZERO,
+            placement: LabelPlacement::default(),
+            thumb: Animated::new(0.),
+        }
+    }
+
+    /// Builder-style method to show `text` beside the switch.
+    pub fn with_label(mut self, text: impl Into<LabelText<bool>>) -> Self {
+        self.label = Some(Label::new(text));
+        self
+    }
+
+    /// Builder-style method to choose the side the label goes on.
+    pub fn label_placement(mut self, placement: LabelPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    fn flip(&mut self, ctx: &mut EventCtx, data: &mut bool) {
+        *data = !*data;
+        if self.thumb.animate_to(if *data { 1. } else { 0. }) {
+            ctx.request_anim_frame();
+        }
+        ctx.request_paint();
+    }
+
+    fn track_origin(&self, height: f64, env: &Env) -> Point {
+        let x = match (&self.label, self.placement) {
+            (Some(_), LabelPlacement::Before) => {
+                self.label_size.width + theme::get(env, theme::SPACING)
+            }
+            _ => 0.,
+        };
+        Point::new(x, (height - TRACK.height) / 2.)
+    }
+}
+
+impl Default for Toggle {
+    fn default() -> Self {
+        Toggle::new()
+    }
+}
+
+impl Widget<bool> for Toggle {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut bool, _env: &Env) {
+        match event {
+            Event::MouseDown(_) if !ctx.is_disabled() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                ctx.request_paint();
+            }
+            Event::MouseUp(_) => {
+                if ctx.is_active() && ctx.is_hot() && !ctx.is_disabled() {
+                    self.flip(ctx, data);
+                }
+                ctx.set_active(false);
+            }
+            Event::KeyDown(key) if !ctx.is_disabled() => match &key.key {
+                KbKey::Enter => self.flip(ctx, data),
+                KbKey::Character(c) if c == " " => self.flip(ctx, data),
+                _ => return,
+            },
+            Event::AnimFrame(nanos) => {
+                if self.thumb.advance(*nanos) {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+                return;
+            }
+            _ => return,
+        }
+        ctx.set_handled();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &bool, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.thumb.jump_to(if *data { 1. } else { 0. }),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::FocusChanged(_)
+            | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+        if let Some(label) = &mut self.label {
+            label.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &bool, data: &bool, env: &Env) {
+        // flips that came from elsewhere in the app slide too
+        let target = if *data { 1. } else { 0. };
+        if *self.thumb.target() != target {
+            if self.thumb.animate_to(target) {
+                ctx.request_anim_frame();
+            }
+            ctx.request_paint();
+        }
+        if ctx.env_changed() {
+            ctx.request_paint();
+        }
+        if let Some(label) = &mut self.label {
+            label.update(ctx, old_data, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &bool, env: &Env) -> Size {
+        bc.debug_check("Toggle");
+        let mut size = TRACK;
+        if let Some(label) = &mut self.label {
+            self.label_size = label.layout(ctx, &bc.loosen(), data, env);
+            size.width += theme::get(env, theme::SPACING) + self.label_size.width;
+            size.height = size.height.max(self.label_size.height);
+            let label_top = (size.height - self.label_size.height) / 2.;
+            ctx.set_baseline_offset(label.baseline_offset() + label_top);
+        }
+        // the focus ring goes around the track
+        ctx.set_paint_insets(FOCUS_GAP + 1.);
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &bool, env: &Env) {
+        let size = ctx.size();
+        let on = self.thumb.value();
+        let disabled = ctx.is_disabled();
+        let accent = theme::get(env, theme::ACCENT);
+
+        let track = Rect::from_origin_size(self.track_origin(size.height, env), TRACK);
+        let radius = TRACK.height / 2.;
+        let fill = if disabled {
+            theme::get(env, theme::SURFACE_DISABLED)
+        } else {
+            theme::get(env, theme::SURFACE).tween(&accent, on)
+        };
+        let border = if ctx.is_hot() && !disabled {
+            // hovering leans towards the accent, like buttons do
+            theme::get(env, theme::BORDER).tween(&accent, 0.5)
+        } else {
+            theme::get(env, theme::BORDER)
+        };
+        let stroke = theme::get(env, theme::BORDER_WIDTH);
+        let rounded = track.inset(-stroke / 2.).to_rounded_rect(radius);
+        ctx.fill(rounded, &fill);
+        ctx.stroke(rounded, &border.tween(&accent, on), stroke);
+
+        let thumb_radius = radius - THUMB_INSET;
+        let travel = TRACK.width - TRACK.height;
+        let center = Point::new(track.x0 + radius + on * travel, track.center().y);
+        let thumb = if disabled {
+            theme::get(env, theme::TEXT_DISABLED)
+        } else {
+            Color::WHITE
+        };
+        ctx.fill(Circle::new(center, thumb_radius), &thumb);
+
+        if ctx.is_focused() {
+            ctx.stroke(
+                track.inset(FOCUS_GAP).to_rounded_rect(radius + FOCUS_GAP),
+                &accent,
+                1.,
+            );
+        }
+
+        if let Some(label) = &mut self.label {
+            let x = match self.placement {
+                LabelPlacement::Before => 0.,
+                LabelPlacement::After => TRACK.width + theme::get(env, theme::SPACING),
+            };
+            let y = (size.height - self.label_size.height) / 2.;
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate((x, y)));
+                label.paint(ctx, data, env);
+            });
+        }
+    }
+}
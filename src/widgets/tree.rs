@@ -0,0 +1,538 @@
+//! A tree of expandable rows, with children loaded on demand.
+//!
+//! The nodes live in the app data as a [`TreeState`]. A node whose `children` are `None`
+//! hasn't been loaded: expanding it calls the tree's loader with a [`ChildLoader`], which
+//! hands the children back from any thread once they are ready:
+//!
+//! ```ignore
+//! Tree::new(|| Label::dynamic(|file: &File, _| file.name.clone())).with_loader(|dir, loader| {
+//!     let path = dir.path.clone();
+//!     std::thread::spawn(move || loader.complete(read_dir(&path)));
+//! })
+//! ```
+//!
+//! Up and Down move the selection, Right expands the selected node or moves into it, Left
+//! collapses it or moves to its parent.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+
+use druid::im::Vector;
+use druid::kurbo::{BezPath, Line};
+use druid::widget::prelude::*;
+use druid::{
+    Command, ExtEventSink, KbKey, Lens, Point, Rect, SingleUse, Target, WidgetExt, WidgetPod,
+};
+use tracing::{debug, warn};
+
+use crate::theme;
+
+const ROW_HEIGHT: f64 = 24.;
+/// Horizontal step between a node and its children, the chevron sits in the first one
+const INDENT: f64 = 18.;
+const CHEVRON: f64 = 4.;
+
+/// Indices from the roots down to a node.
+pub type TreePath = Vector<usize>;
+
+type Loaded = (TreePath, Box<dyn Any + Send>);
+
+crate::selectors! {
+    /// Sent by a `ChildLoader` to its tree, the children are a `Vector<TreeNode<T>>`
+    pub(crate) CHILDREN_LOADED: SingleUse<Loaded>,
+}
+
+#[derive(Debug, Clone, Data, Lens)]
+pub struct TreeNode<T: Clone> {
+    pub item: T,
+    /// `None` until loaded, the [`Tree`]'s loader is asked for them on the first expand
+    pub children: Option<Vector<TreeNode<T>>>,
+    pub expanded: bool,
+}
+
+impl<T: Clone> TreeNode<T> {
+    pub fn leaf(item: T) -> Self {
+        TreeNode {
+            item,
+            children: Some(Vector::new()),
+            expanded: false,
+        }
+    }
+
+    pub fn branch(item: T, children: impl IntoIterator<Item = TreeNode<T>>) -> Self {
+        TreeNode {
+            item,
+            children: Some(children.into_iter().collect()),
+            expanded: false,
+        }
+    }
+
+    /// A node whose children are loaded when it is first expanded.
+    pub fn unloaded(item: T) -> Self {
+        TreeNode {
+            item,
+            children: None,
+            expanded: false,
+        }
+    }
+
+    /// Builder-style method to show the node expanded or collapsed at first.
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+}
+
+/// The nodes of a [`Tree`] and the selected one.
+#[derive(Debug, Clone, Data, Lens)]
+pub struct TreeState<T: Clone> {
+    pub roots: Vector<TreeNode<T>>,
+    pub selected: Option<TreePath>,
+}
+
+impl<T: Clone> TreeState<T> {
+    pub fn new(roots: Vector<TreeNode<T>>) -> Self {
+        TreeState {
+            roots,
+            selected: None,
+        }
+    }
+
+    pub fn node(&self, path: &TreePath) -> Option<&TreeNode<T>> {
+        let mut indices = path.iter();
+        let mut node = self.roots.get(*indices.next()?)?;
+        for &index in indices {
+            node = node.children.as_ref()?.get(index)?;
+        }
+        Some(node)
+    }
+
+    pub fn node_mut(&mut self, path: &TreePath) -> Option<&mut TreeNode<T>> {
+        let mut indices = path.iter();
+        let mut node = self.roots.get_mut(*indices.next()?)?;
+        for &index in indices {
+            node = node.children.as_mut()?.get_mut(index)?;
+        }
+        Some(node)
+    }
+
+    pub fn selected_node(&self) -> Option<&TreeNode<T>> {
+        self.node(self.selected.as_ref()?)
+    }
+}
+
+/// Hands the children of an expanded node back to the [`Tree`] that asked for them.
+pub struct ChildLoader {
+    sink: ExtEventSink,
+    tree: WidgetId,
+    path: TreePath,
+}
+
+impl ChildLoader {
+    /// The node being loaded.
+    pub fn path(&self) -> &TreePath {
+        &self.path
+    }
+
+    /// Delivers the children, from any thread.
+    pub fn complete<T: Clone + Send + Sync + 'static>(self, children: Vector<TreeNode<T>>) {
+        let loaded: Loaded = (self.path, Box::new(children));
+        let sent = self.sink.submit_command(
+            CHILDREN_LOADED,
+            SingleUse::new(loaded),
+            Target::Widget(self.tree),
+        );
+        if sent.is_err() {
+            warn!("tree children loaded after the app quit");
+        }
+    }
+
+    /// The command delivering the children, for loaders that finish on the UI thread.
+    pub fn into_command<T: Clone + Send + Sync + 'static>(
+        self,
+        children: Vector<TreeNode<T>>,
+    ) -> Command {
+        let loaded: Loaded = (self.path, Box::new(children));
+        CHILDREN_LOADED
+            .with(SingleUse::new(loaded))
+            .to(Target::Widget(self.tree))
+    }
+}
+
+type RowFn<T> = Box<dyn Fn() -> Box<dyn Widget<T>>>;
+type LoaderFn<T> = Box<dyn Fn(&T, ChildLoader)>;
+
+struct Row<T> {
+    path: TreePath,
+    depth: usize,
+    expandable: bool,
+    expanded: bool,
+    view: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+pub struct Tree<T> {
+    make_row: RowFn<T>,
+    loader: Option<LoaderFn<T>>,
+    /// The nodes in view, parents before their children
+    rows: Vec<Row<T>>,
+    /// Nodes whose loader hasn't delivered yet
+    loading: HashSet<TreePath>,
+    hot: Option<usize>,
+}
+
+impl<T: Data> Tree<T> {
+    /// A tree showing each node's item with a widget built by `make_row`.
+    pub fn new<W: Widget<T> + 'static>(make_row: impl Fn() -> W + 'static) -> Self {
+        Tree {
+            make_row: Box::new(move || make_row().boxed()),
+            loader: None,
+            rows: Vec::new(),
+            loading: HashSet::new(),
+            hot: None,
+        }
+    }
+
+    /// Called with the item of an unloaded node when it is first expanded.
+    pub fn with_loader(mut self, loader: impl Fn(&T, ChildLoader) + 'static) -> Self {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    fn expandable(&self, node: &TreeNode<T>) -> bool {
+        node.children
+            .as_ref()
+            .map_or(self.loader.is_some(), |children| !children.is_empty())
+    }
+
+    /// Lists the nodes in view again, keeping the row widgets of nodes that stay. Returns
+    /// whether rows were built or dropped.
+    fn rebuild_rows(&mut self, data: &TreeState<T>) -> bool {
+        let mut old: HashMap<_, _> = self
+            .rows
+            .drain(..)
+            .map(|row| (row.path.clone(), row.view))
+            .collect();
+        let mut changed = false;
+        let mut stack: Vec<_> = data
+            .roots
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, node)| (Vector::unit(index), node))
+            .collect();
+        while let Some((path, node)) = stack.pop() {
+            let view = old.remove(&path).unwrap_or_else(|| {
+                changed = true;
+                WidgetPod::new((self.make_row)())
+            });
+            if node.expanded
+                && let Some(children) = &node.children
+            {
+                for (index, child) in children.iter().enumerate().rev() {
+                    let mut child_path = path.clone();
+                    child_path.push_back(index);
+                    stack.push((child_path, child));
+                }
+            }
+            self.rows.push(Row {
+                depth: path.len() - 1,
+                expandable: self.expandable(node),
+                expanded: node.expanded,
+                path,
+                view,
+            });
+        }
+        changed || !old.is_empty()
+    }
+
+    fn row_of(&self, path: &TreePath) -> Option<usize> {
+        self.rows.iter().position(|row| &row.path == path)
+    }
+
+    fn toggle(&mut self, ctx: &mut EventCtx, data: &mut TreeState<T>, path: &TreePath) {
+        let Some(node) = data.node_mut(path) else {
+            return;
+        };
+        node.expanded = !node.expanded;
+        if !node.expanded {
+            // the selection doesn't disappear into a collapsed node
+            if data.selected.as_ref().is_some_and(|selected| {
+                selected.len() > path.len() && selected.take(path.len()) == *path
+            }) {
+                data.selected = Some(path.clone());
+            }
+            return;
+        }
+        if node.children.is_none()
+            && let Some(loader) = &self.loader
+            && self.loading.insert(path.clone())
+        {
+            debug!(?path, "loading tree children");
+            let child_loader = ChildLoader {
+                sink: ctx.get_external_handle(),
+                tree: ctx.widget_id(),
+                path: path.clone(),
+            };
+            loader(&node.item, child_loader);
+        }
+    }
+
+    fn event_rows(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TreeState<T>,
+        env: &Env,
+    ) {
+        for row in &mut self.rows {
+            if !row.view.is_initialized() {
+                continue;
+            }
+            let Some(node) = data.node(&row.path) else {
+                continue;
+            };
+            let mut item = node.item.clone();
+            row.view.event(ctx, event, &mut item, env);
+            if !item.same(&node.item)
+                && let Some(node) = data.node_mut(&row.path)
+            {
+                node.item = item;
+            }
+        }
+    }
+}
+
+impl<T: Data> Widget<TreeState<T>> for Tree<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut TreeState<T>, env: &Env) {
+        crate::match_command!(event, ctx => {
+            Command(CHILDREN_LOADED(loaded)) => {
+                let Some((path, children)) = loaded.take() else {
+                    return;
+                };
+                self.loading.remove(&path);
+                match (children.downcast::<Vector<TreeNode<T>>>(), data.node_mut(&path)) {
+                    (Ok(children), Some(node)) => node.children = Some(*children),
+                    (Err(_), _) => warn!(?path, "tree children of the wrong type"),
+                    (_, None) => debug!(?path, "tree children loaded for a removed node"),
+                }
+                ctx.request_paint();
+            }
+            _ => {}
+        });
+        if ctx.is_handled() {
+            return;
+        }
+
+        self.event_rows(ctx, event, data, env);
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) => {
+                let index = (mouse.pos.y / ROW_HEIGHT) as usize;
+                if let Some(row) = self.rows.get(index).filter(|_| mouse.pos.y >= 0.) {
+                    let path = row.path.clone();
+                    let chevron = row.depth as f64 * INDENT;
+                    let on_chevron = (chevron..chevron + INDENT).contains(&mouse.pos.x);
+                    if row.expandable && (on_chevron || mouse.count == 2) {
+                        self.toggle(ctx, data, &path);
+                    } else {
+                        data.selected = Some(path);
+                    }
+                }
+                ctx.request_focus();
+            }
+            Event::MouseMove(mouse) => {
+                let index = (mouse.pos.y / ROW_HEIGHT) as usize;
+                let hot = (index < self.rows.len() && mouse.pos.y >= 0.).then_some(index);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::KeyDown(key) if !self.rows.is_empty() => {
+                let last = self.rows.len() - 1;
+                let current = data.selected.as_ref().and_then(|path| self.row_of(path));
+                let next = match (&key.key, current) {
+                    (KbKey::ArrowDown, Some(index)) => (index + 1).min(last),
+                    (KbKey::ArrowUp, Some(index)) => index.saturating_sub(1),
+                    (KbKey::ArrowDown | KbKey::ArrowUp | KbKey::Home, _) => 0,
+                    (KbKey::End, _) => last,
+                    (KbKey::ArrowRight, Some(index)) => {
+                        let row = &self.rows[index];
+                        if row.expandable && !row.expanded {
+                            let path = row.path.clone();
+                            self.toggle(ctx, data, &path);
+                            ctx.set_handled();
+                            return;
+                        }
+                        // an expanded node moves into its first child
+                        match self.rows.get(index + 1) {
+                            Some(child) if child.depth > row.depth => index + 1,
+                            _ => index,
+                        }
+                    }
+                    (KbKey::ArrowLeft, Some(index)) => {
+                        let row = &self.rows[index];
+                        if row.expanded {
+                            let path = row.path.clone();
+                            self.toggle(ctx, data, &path);
+                            ctx.set_handled();
+                            return;
+                        }
+                        let parent = row.path.take(row.path.len() - 1);
+                        self.row_of(&parent).unwrap_or(index)
+                    }
+                    _ => return,
+                };
+                data.selected = Some(self.rows[next].path.clone());
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &TreeState<T>,
+        env: &Env,
+    ) {
+        match event {
+            // the rows built here get their WidgetAdded right below
+            LifeCycle::WidgetAdded => {
+                self.rebuild_rows(data);
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(false) => {
+                self.hot = None;
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+        let routed = matches!(event, LifeCycle::WidgetAdded | LifeCycle::Internal(_));
+        for row in &mut self.rows {
+            if let Some(node) = data.node(&row.path)
+                && (routed || row.view.is_initialized())
+            {
+                row.view.lifecycle(ctx, event, &node.item, env);
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &TreeState<T>,
+        data: &TreeState<T>,
+        env: &Env,
+    ) {
+        if !old_data.roots.same(&data.roots) {
+            if self.rebuild_rows(data) {
+                ctx.children_changed();
+            }
+            ctx.request_layout();
+        }
+        // rows built above are only updated once they have been added
+        for row in &mut self.rows {
+            if let Some(node) = data.node(&row.path)
+                && row.view.is_initialized()
+            {
+                row.view.update(ctx, &node.item, env);
+            }
+        }
+        if old_data.selected != data.selected {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &TreeState<T>,
+        env: &Env,
+    ) -> Size {
+        let mut width: f64 = 0.;
+        for (index, row) in self.rows.iter_mut().enumerate() {
+            let Some(node) = data.node(&row.path) else {
+                continue;
+            };
+            if !row.view.is_initialized() {
+                continue;
+            }
+            let x = (row.depth + 1) as f64 * INDENT;
+            let row_bc = BoxConstraints::new(
+                Size::new(0., ROW_HEIGHT),
+                Size::new((bc.max().width - x).max(0.), ROW_HEIGHT),
+            );
+            let size = row.view.layout(ctx, &row_bc, &node.item, env);
+            row.view
+                .set_origin(ctx, Point::new(x, index as f64 * ROW_HEIGHT));
+            width = width.max(x + size.width);
+        }
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            width
+        };
+        bc.constrain(Size::new(width, self.rows.len() as f64 * ROW_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &TreeState<T>, env: &Env) {
+        let width = ctx.size().width;
+        let border = theme::get(env, theme::BORDER);
+        let text = theme::get(env, theme::TEXT);
+        let loading = theme::get(env, theme::TEXT_DISABLED);
+        let row_rect = |index: usize| {
+            let y = index as f64 * ROW_HEIGHT;
+            Rect::new(0., y, width, y + ROW_HEIGHT)
+        };
+
+        if let Some(hot) = self.hot {
+            ctx.fill(row_rect(hot), &theme::get(env, theme::SURFACE));
+        }
+        if let Some(selected) = data.selected.as_ref().and_then(|path| self.row_of(path)) {
+            ctx.fill(
+                row_rect(selected),
+                &theme::get(env, theme::ACCENT).with_alpha(0.35),
+            );
+        }
+
+        for (index, row) in self.rows.iter_mut().enumerate() {
+            let rect = row_rect(index);
+            // a guide for each ancestor, through the middle of its chevron
+            for depth in 0..row.depth {
+                let x = (depth as f64 + 0.5) * INDENT;
+                ctx.stroke(Line::new((x, rect.y0), (x, rect.y1)), &border, 1.);
+            }
+            if row.expandable {
+                let center = Point::new((row.depth as f64 + 0.5) * INDENT, rect.center().y);
+                let mut chevron = BezPath::new();
+                if row.expanded {
+                    chevron.move_to((center.x - CHEVRON, center.y - CHEVRON / 2.));
+                    chevron.line_to((center.x + CHEVRON, center.y - CHEVRON / 2.));
+                    chevron.line_to((center.x, center.y + CHEVRON / 2.));
+                } else {
+                    chevron.move_to((center.x - CHEVRON / 2., center.y - CHEVRON));
+                    chevron.line_to((center.x + CHEVRON / 2., center.y));
+                    chevron.line_to((center.x - CHEVRON / 2., center.y + CHEVRON));
+                }
+                chevron.close_path();
+                let color = if self.loading.contains(&row.path) {
+                    &loading
+                } else {
+                    &text
+                };
+                ctx.fill(chevron, color);
+            }
+            if let Some(node) = data.node(&row.path)
+                && row.view.is_initialized()
+            {
+                row.view.paint(ctx, &node.item, env);
+            }
+        }
+    }
+}
@@ -0,0 +1,476 @@
+//! A list that only builds, lays out and paints the rows in view.
+//!
+//! Rows are widgets like in druid's `List`, but a [`VirtualList`] scrolls by itself and
+//! keeps widgets for the visible rows only, so a `Vector` of a few hundred thousand items
+//! costs no more than a screenful of them.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use druid::im::Vector;
+use druid::widget::prelude::*;
+use druid::{KbKey, Lens, Point, Rect, WidgetExt, WidgetPod};
+use tracing::trace;
+
+use crate::theme;
+
+/// Rows built beyond each edge of the view, so short scrolls don't show gaps
+const OVERSCAN: usize = 2;
+/// Height used when the list is laid out without a bound on its height
+const DEFAULT_HEIGHT: f64 = 300.;
+const SCROLLBAR_WIDTH: f64 = 4.;
+
+crate::selectors! {
+    /// Scrolls the list this is sent to until the row at the index is in view
+    SCROLL_TO_INDEX: usize,
+}
+
+crate::selectors! {
+    /// The list asks itself for rows it found missing while laying out
+    pub(crate) SYNC_ROWS,
+}
+
+/// The items of a [`VirtualList`] and the selected one.
+#[derive(Debug, Clone, Data, Lens)]
+pub struct VirtualListState<T: Clone> {
+    pub items: Vector<T>,
+    pub selected: Option<usize>,
+}
+
+impl<T: Clone> VirtualListState<T> {
+    pub fn new(items: Vector<T>) -> Self {
+        VirtualListState {
+            items,
+            selected: None,
+        }
+    }
+
+    pub fn selected_item(&self) -> Option<&T> {
+        self.items.get(self.selected?)
+    }
+}
+
+/// How tall rows are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// Every row is this tall, the cheapest option
+    Fixed(f64),
+    /// Rows are as tall as their widget wants, rows not laid out yet count as `estimate`
+    Measured { estimate: f64 },
+}
+
+type RowFn<T> = Box<dyn Fn() -> Box<dyn Widget<T>>>;
+
+pub struct VirtualList<T> {
+    make_row: RowFn<T>,
+    row_height: RowHeight,
+    rows: BTreeMap<usize, WidgetPod<T, Box<dyn Widget<T>>>>,
+    /// Heights of measured rows, estimates for the rest
+    heights: Vec<f64>,
+    /// `tops[i]` is where row `i` starts, one more entry for the total height
+    tops: Vec<f64>,
+    scroll: f64,
+    viewport: Size,
+    scroll_to: Option<usize>,
+    hot: Option<usize>,
+}
+
+impl<T: Data> VirtualList<T> {
+    /// A list of fixed height rows built with `make_row`.
+    pub fn new<W: Widget<T> + 'static>(make_row: impl Fn() -> W + 'static, height: f64) -> Self {
+        VirtualList {
+            make_row: Box::new(move || make_row().boxed()),
+            row_height: RowHeight::Fixed(height),
+            rows: BTreeMap::new(),
+            heights: Vec::new(),
+            tops: vec![0.],
+            scroll: 0.,
+            // a guess until the first layout, to build the first rows with the widget
+            viewport: Size::new(0., DEFAULT_HEIGHT),
+            scroll_to: None,
+            hot: None,
+        }
+    }
+
+    pub fn with_row_height(mut self, row_height: RowHeight) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    fn set_len(&mut self, len: usize) {
+        if let RowHeight::Measured { estimate } = self.row_height
+            && self.heights.len() != len
+        {
+            self.heights.resize(len, estimate);
+            self.update_tops();
+        }
+        self.rows.retain(|&index, _| index < len);
+    }
+
+    fn update_tops(&mut self) {
+        self.tops.clear();
+        self.tops.push(0.);
+        let mut top = 0.;
+        for height in &self.heights {
+            top += height;
+            self.tops.push(top);
+        }
+    }
+
+    fn row_top(&self, index: usize) -> f64 {
+        match self.row_height {
+            RowHeight::Fixed(height) => index as f64 * height,
+            RowHeight::Measured { .. } => self.tops[index.min(self.heights.len())],
+        }
+    }
+
+    fn row_bottom(&self, index: usize) -> f64 {
+        self.row_top(index + 1)
+    }
+
+    fn total_height(&self, len: usize) -> f64 {
+        self.row_top(len)
+    }
+
+    /// The row at `y` in list coordinates, `None` below the last row.
+    fn index_at(&self, y: f64, len: usize) -> Option<usize> {
+        if y < 0. || y >= self.total_height(len) {
+            return None;
+        }
+        let index = match self.row_height {
+            RowHeight::Fixed(height) => (y / height) as usize,
+            RowHeight::Measured { .. } => self.tops.partition_point(|&top| top <= y) - 1,
+        };
+        Some(index.min(len - 1))
+    }
+
+    fn visible_range(&self, len: usize) -> Range<usize> {
+        if len == 0 {
+            return 0..0;
+        }
+        let first = self.index_at(self.scroll, len).unwrap_or(len - 1);
+        let last = self
+            .index_at(self.scroll + self.viewport.height, len)
+            .unwrap_or(len - 1);
+        first.saturating_sub(OVERSCAN)..(last + OVERSCAN + 1).min(len)
+    }
+
+    fn clamp_scroll(&mut self, len: usize) {
+        let max = (self.total_height(len) - self.viewport.height).max(0.);
+        self.scroll = self.scroll.clamp(0., max);
+    }
+
+    fn reveal(&mut self, index: usize) {
+        let top = self.row_top(index);
+        let bottom = self.row_bottom(index);
+        if top < self.scroll {
+            self.scroll = top;
+        } else if bottom > self.scroll + self.viewport.height {
+            self.scroll = bottom - self.viewport.height;
+        }
+    }
+
+    /// Builds the rows coming into view and drops the ones that left it, returns whether
+    /// anything changed.
+    fn sync_rows(&mut self, len: usize) -> bool {
+        let range = self.visible_range(len);
+        let before = self.rows.len();
+        self.rows.retain(|index, _| range.contains(index));
+        let mut changed = self.rows.len() != before;
+        for index in range {
+            self.rows.entry(index).or_insert_with(|| {
+                changed = true;
+                WidgetPod::new((self.make_row)())
+            });
+        }
+        if changed {
+            trace!(rows = self.rows.len(), "virtual list rows synced");
+        }
+        changed
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, data: &mut VirtualListState<T>, index: usize) {
+        data.selected = Some(index);
+        self.reveal(index);
+        if self.sync_rows(data.items.len()) {
+            ctx.children_changed();
+        }
+        ctx.request_layout();
+    }
+
+    fn page_rows(&self) -> usize {
+        let height = match self.row_height {
+            RowHeight::Fixed(height) => height,
+            RowHeight::Measured { estimate } => estimate,
+        };
+        ((self.viewport.height / height) as usize).max(1)
+    }
+}
+
+impl<T: Data> Widget<VirtualListState<T>> for VirtualList<T> {
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut VirtualListState<T>,
+        env: &Env,
+    ) {
+        let len = data.items.len();
+        crate::match_command!(event, ctx => {
+            Command(SYNC_ROWS) => {
+                if self.sync_rows(len) {
+                    ctx.children_changed();
+                    ctx.request_layout();
+                }
+            }
+            Command(SCROLL_TO_INDEX(index)) => {
+                // rows may not be measured yet, layout scrolls once they are
+                self.scroll_to = Some(*index);
+                ctx.request_layout();
+            }
+            _ => {
+                self.event_rows(ctx, event, data, env);
+                if ctx.is_handled() {
+                    return;
+                }
+                match event {
+                    Event::Wheel(mouse) => {
+                        self.scroll += mouse.wheel_delta.y;
+                        self.clamp_scroll(len);
+                        if self.sync_rows(len) {
+                            ctx.children_changed();
+                        }
+                        ctx.request_layout();
+                        ctx.set_handled();
+                    }
+                    Event::MouseDown(mouse) => {
+                        if let Some(index) = self.index_at(mouse.pos.y + self.scroll, len) {
+                            self.select(ctx, data, index);
+                        }
+                        ctx.request_focus();
+                    }
+                    Event::MouseMove(mouse) => {
+                        let hot = self.index_at(mouse.pos.y + self.scroll, len);
+                        if hot != self.hot {
+                            self.hot = hot;
+                            ctx.request_paint();
+                        }
+                    }
+                    Event::KeyDown(key) if len > 0 => {
+                        let last = len - 1;
+                        let current = data.selected.map(|index| index.min(last));
+                        let next = match &key.key {
+                            KbKey::ArrowDown => current.map_or(0, |index| (index + 1).min(last)),
+                            KbKey::ArrowUp => current.map_or(0, |index| index.saturating_sub(1)),
+                            KbKey::PageDown => {
+                                current.map_or(0, |index| (index + self.page_rows()).min(last))
+                            }
+                            KbKey::PageUp => current
+                                .map_or(0, |index| index.saturating_sub(self.page_rows())),
+                            KbKey::Home => 0,
+                            KbKey::End => last,
+                            _ => return,
+                        };
+                        self.select(ctx, data, next);
+                        ctx.set_handled();
+                    }
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &VirtualListState<T>,
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                // the rows built here get their WidgetAdded right below
+                self.set_len(data.items.len());
+                self.sync_rows(data.items.len());
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(false) => {
+                self.hot = None;
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+        let routed = matches!(event, LifeCycle::WidgetAdded | LifeCycle::Internal(_));
+        for (&index, row) in &mut self.rows {
+            if let Some(item) = data.items.get(index)
+                && (routed || row.is_initialized())
+            {
+                row.lifecycle(ctx, event, item, env);
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &VirtualListState<T>,
+        data: &VirtualListState<T>,
+        env: &Env,
+    ) {
+        for (&index, row) in &mut self.rows {
+            if let Some(item) = data.items.get(index)
+                && row.is_initialized()
+            {
+                row.update(ctx, item, env);
+            }
+        }
+        // rows built here are only updated once they have been added
+        let len = data.items.len();
+        if old_data.items.len() != len {
+            self.set_len(len);
+            self.clamp_scroll(len);
+            if self.sync_rows(len) {
+                ctx.children_changed();
+            }
+            ctx.request_layout();
+        }
+        if old_data.selected != data.selected {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &VirtualListState<T>,
+        env: &Env,
+    ) -> Size {
+        let len = data.items.len();
+        let height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            self.total_height(len).min(DEFAULT_HEIGHT)
+        };
+        self.viewport = bc.constrain(Size::new(bc.max().width, height));
+
+        let width = self.viewport.width;
+        let row_bc = match self.row_height {
+            RowHeight::Fixed(height) => BoxConstraints::tight(Size::new(width, height)),
+            RowHeight::Measured { .. } => {
+                BoxConstraints::new(Size::new(width, 0.), Size::new(width, f64::INFINITY))
+            }
+        };
+        let mut remeasured = false;
+        for (&index, row) in &mut self.rows {
+            let Some(item) = data.items.get(index) else {
+                continue;
+            };
+            if !row.is_initialized() {
+                continue;
+            }
+            let size = row.layout(ctx, &row_bc, item, env);
+            if let Some(height) = self.heights.get_mut(index)
+                && *height != size.height
+            {
+                *height = size.height;
+                remeasured = true;
+            }
+        }
+        if remeasured {
+            self.update_tops();
+        }
+
+        if let Some(index) = self.scroll_to.take()
+            && index < len
+        {
+            self.reveal(index);
+        }
+        self.clamp_scroll(len);
+        for (&index, row) in &mut self.rows {
+            let top = match self.row_height {
+                RowHeight::Fixed(height) => index as f64 * height,
+                RowHeight::Measured { .. } => self.tops[index],
+            };
+            row.set_origin(ctx, Point::new(0., top - self.scroll));
+        }
+
+        // rows can only be added outside of layout, ask for them once it is done
+        let range = self.visible_range(len);
+        if range.clone().any(|index| !self.rows.contains_key(&index))
+            || self.rows.keys().any(|index| !range.contains(index))
+        {
+            ctx.submit_command(SYNC_ROWS.to(ctx.widget_id()));
+        }
+        self.viewport
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &VirtualListState<T>, env: &Env) {
+        let size = ctx.size();
+        let len = data.items.len();
+        let accent = theme::get(env, theme::ACCENT);
+        let surface = theme::get(env, theme::SURFACE);
+
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            let row_rect = |index: usize| {
+                Rect::new(
+                    0.,
+                    self.row_top(index) - self.scroll,
+                    size.width,
+                    self.row_bottom(index) - self.scroll,
+                )
+            };
+            if let Some(hot) = self.hot
+                && hot < len
+            {
+                ctx.fill(row_rect(hot), &surface);
+            }
+            if let Some(selected) = data.selected
+                && selected < len
+            {
+                ctx.fill(row_rect(selected), &accent.with_alpha(0.35));
+            }
+            for (&index, row) in &mut self.rows {
+                if let Some(item) = data.items.get(index)
+                    && row.is_initialized()
+                {
+                    row.paint(ctx, item, env);
+                }
+            }
+        });
+
+        let total = self.total_height(len);
+        if total > size.height {
+            let thumb = (size.height / total * size.height).max(16.);
+            let y = self.scroll / (total - size.height) * (size.height - thumb);
+            let rect = Rect::new(size.width - SCROLLBAR_WIDTH, y, size.width, y + thumb)
+                .to_rounded_rect(SCROLLBAR_WIDTH / 2.);
+            ctx.fill(rect, &theme::get(env, theme::BORDER));
+        }
+    }
+}
+
+impl<T: Data> VirtualList<T> {
+    fn event_rows(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut VirtualListState<T>,
+        env: &Env,
+    ) {
+        for (&index, row) in &mut self.rows {
+            let Some(item) = data.items.get(index) else {
+                continue;
+            };
+            if !row.is_initialized() {
+                continue;
+            }
+            let mut item = item.clone();
+            row.event(ctx, event, &mut item, env);
+            if !item.same(&data.items[index]) {
+                data.items.set(index, item);
+            }
+        }
+    }
+}
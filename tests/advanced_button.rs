@@ -0,0 +1,92 @@
+mod harness;
+
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{Color, Key, Selector, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use druid_gio_widgets::theme;
+use harness::render;
+
+const SIZE: Size = Size::new(120., 40.);
+const FILL: Color = Color::rgb8(43, 45, 48);
+const ACCENT: Color = Color::rgb8(52, 116, 240);
+const CLICKS: Key<f64> = Key::new("tests.advanced_button.clicks");
+const BUMP: Selector = Selector::new("tests.advanced_button.bump");
+
+// Stands in for the part of the app changing data the button doesn't show.
+struct Bump;
+
+impl<W: Widget<u32>> Controller<u32, W> for Bump {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut u32,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event
+            && cmd.is(BUMP)
+        {
+            *data += 1;
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+#[test]
+fn fills_whole_area() {
+    let image = render((), AdvancedButton::new("OK"), SIZE, |_| {});
+    assert_eq!(image.pixel(1, 20), FILL);
+    assert_eq!(image.pixel(118, 20), FILL);
+}
+
+#[test]
+fn press_draws_thick_accent_border() {
+    let image = render((), AdvancedButton::new("OK"), SIZE, |h| {
+        h.mouse_move((60., 20.));
+        h.mouse_down((60., 20.));
+        h.settle();
+    });
+    assert_eq!(image.pixel(1, 20), ACCENT);
+}
+
+#[test]
+fn release_ends_press() {
+    let image = render((), AdvancedButton::new("OK"), SIZE, |h| {
+        h.click((60., 20.));
+        h.settle();
+    });
+    assert_eq!(image.pixel(1, 20), FILL);
+}
+
+#[test]
+fn press_eases_in() {
+    let image = render((), AdvancedButton::new("OK"), SIZE, |h| {
+        h.mouse_move((60., 20.));
+        h.mouse_down((60., 20.));
+    });
+    // nothing has moved before the first frame
+    assert_eq!(image.pixel(1, 20), FILL);
+}
+
+#[test]
+fn follows_light_preset() {
+    let button = AdvancedButton::new("OK").env_scope(theme::light);
+    let image = render((), button, SIZE, |_| {});
+    assert_eq!(image.pixel(1, 20), Color::WHITE);
+}
+
+#[test]
+fn other_env_changes_keep_easing_in() {
+    let button = AdvancedButton::new("OK")
+        .env_scope(|env, clicks: &u32| env.set(CLICKS, *clicks as f64))
+        .controller(Bump);
+    let image = render(0, button, SIZE, |h| {
+        h.mouse_move((60., 20.));
+        h.mouse_down((60., 20.));
+        h.submit_command(BUMP);
+    });
+    // still where the press started, the new `Env` didn't skip to the end
+    assert_eq!(image.pixel(1, 20), FILL);
+}
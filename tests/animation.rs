@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use druid::Color;
+use druid_gio_widgets::animation::{Animated, Easing, Timeline, Tween};
+
+const MS: u64 = 1_000_000;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn easings_start_and_end_in_place() {
+    for easing in [
+        Easing::Linear,
+        Easing::EASE,
+        Easing::EASE_IN_OUT,
+        Easing::SPRING,
+    ] {
+        assert_eq!(easing.ease(0.), 0.);
+        assert_eq!(easing.ease(1.), 1.);
+    }
+}
+
+#[test]
+fn symmetric_bezier_is_halfway_at_half_time() {
+    assert!(close(Easing::EASE_IN_OUT.ease(0.5), 0.5));
+    assert!(Easing::EASE_OUT.ease(0.25) > 0.25);
+    assert!(Easing::EASE_IN.ease(0.25) < 0.25);
+}
+
+#[test]
+fn colors_tween_per_channel() {
+    let gray = Color::BLACK.tween(&Color::WHITE, 0.5);
+    let (r, g, b, a) = gray.as_rgba();
+    // colors keep 8 bits per channel
+    let halfway = |channel: f64| (channel - 0.5).abs() <= 1. / 255.;
+    assert!(halfway(r) && halfway(g) && halfway(b));
+    assert_eq!(a, 1.);
+}
+
+#[test]
+fn animated_value_reaches_target() {
+    let mut value = Animated::new(0.)
+        .with_duration(Duration::from_millis(100))
+        .with_easing(Easing::Linear);
+    assert!(value.animate_to(10.));
+    assert!(value.advance(50 * MS));
+    assert!(close(value.value(), 5.));
+    assert!(!value.advance(50 * MS));
+    assert_eq!(value.value(), 10.);
+}
+
+#[test]
+fn retargeting_starts_from_current_value() {
+    let mut value = Animated::new(0.)
+        .with_duration(Duration::from_millis(100))
+        .with_easing(Easing::Linear);
+    value.animate_to(10.);
+    value.advance(50 * MS);
+    value.animate_to(0.);
+    assert!(close(value.value(), 5.));
+}
+
+#[test]
+fn timeline_plays_segments_in_order() {
+    let timeline = Timeline::new(0.)
+        .then(1., Duration::from_millis(100), Easing::Linear)
+        .wait(Duration::from_millis(100))
+        .then(0., Duration::from_millis(100), Easing::Linear);
+    assert_eq!(timeline.duration(), Duration::from_millis(300));
+    assert!(close(timeline.value_at(Duration::from_millis(50)), 0.5));
+    assert_eq!(timeline.value_at(Duration::from_millis(150)), 1.);
+    assert!(close(timeline.value_at(Duration::from_millis(250)), 0.5));
+    assert_eq!(timeline.value_at(Duration::from_secs(1)), 0.);
+}
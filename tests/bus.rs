@@ -0,0 +1,155 @@
+mod harness;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, Controller, Flex, Label, ViewSwitcher};
+use druid::{Command, Selector, Target, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+const PUBLISH: Selector<Command> = Selector::new("tests.bus.publish");
+const LEAVE: Selector = Selector::new("tests.bus.leave");
+
+selectors! {
+    PING: u32,
+}
+
+// Stands in for a widget in another window, the harness only has the one.
+struct Publisher;
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Publisher {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event
+            && let Some(published) = cmd.get(PUBLISH)
+        {
+            ctx.publish(published.clone());
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+// Removes the listener from the tree, dropping its subscription with it.
+struct Leave;
+
+impl<W: Widget<bool>> Controller<bool, W> for Leave {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut bool,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event
+            && cmd.is(LEAVE)
+        {
+            *data = false;
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+// Writes down every ping that reaches the widget.
+struct Spy(Rc<RefCell<Vec<u32>>>);
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Spy {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event
+            && let Some(ping) = cmd.get(PING)
+        {
+            self.0.borrow_mut().push(*ping);
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+/// A listener subscribed to `PING` while the data is `true`, next to a publisher.
+fn listener(pings: Rc<RefCell<Vec<u32>>>) -> impl Widget<bool> {
+    let listener = ViewSwitcher::new(
+        |listening: &bool, _| *listening,
+        move |listening, _, _| {
+            if *listening {
+                Label::new("listening")
+                    .controller(Subscribe::to(PING))
+                    .controller(Spy(pings.clone()))
+                    .boxed()
+            } else {
+                Label::new("gone").boxed()
+            }
+        },
+    );
+    Flex::column()
+        .with_child(listener)
+        .with_child(Label::new("publisher").controller(Publisher))
+        .controller(Leave)
+}
+
+#[test]
+fn subscriber_gets_published_commands() {
+    let pings = Rc::new(RefCell::new(Vec::new()));
+    mount(true, listener(pings.clone()), |h| {
+        h.submit_command(PUBLISH.with(PING.with(7)));
+        assert_eq!(*pings.borrow(), [7]);
+
+        // sent to the subscriber alone, not broadcast
+        let sent = h.commands_of(PING);
+        assert_eq!(sent.len(), 1);
+        assert!(matches!(sent[0].target(), Target::Widget(_)));
+    });
+}
+
+#[test]
+fn dropped_subscription_gets_nothing() {
+    let pings = Rc::new(RefCell::new(Vec::new()));
+    mount(true, listener(pings.clone()), |h| {
+        h.submit_command(LEAVE);
+        assert!(!*h.data());
+
+        h.submit_command(PUBLISH.with(PING.with(7)));
+        assert!(pings.borrow().is_empty());
+        assert!(h.commands_of(PING).is_empty());
+    });
+}
+
+#[test]
+fn dropdown_hears_its_popup_close() {
+    let header = Button::new("Select place")
+        .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(DROPDOWN_SHOW));
+    let dropdown = Dropdown::new(header, |_, _| Label::new("popup")).controller(Publisher);
+    mount(String::new(), dropdown, |h| {
+        h.click((5., 5.));
+        h.submit_command(DROPDOWN_HIDE.to(h.widget_id()));
+        let popup = h.dismissed_windows()[0];
+
+        // what the popup publishes once its window is gone
+        h.submit_command(PUBLISH.with(DROPDOWN_CLOSED.with(popup)));
+        let closed = h.commands_of(DROPDOWN_CLOSED);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].target(), Target::Widget(h.widget_id()));
+
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 2);
+    });
+}
+
+#[test]
+fn select_hears_its_popup_close() {
+    let header = Button::new("Select place")
+        .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(SELECT_SHOW));
+    let select = Select::new(header, ["England"]).controller(Publisher);
+    mount(String::new(), select, |h| {
+        h.click((5., 5.));
+        h.submit_command(SELECT_HIDE.to(h.widget_id()));
+        let popup = h.dismissed_windows()[0];
+
+        h.submit_command(PUBLISH.with(SELECT_CLOSED.with(popup)));
+        let closed = h.commands_of(SELECT_CLOSED);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].target(), Target::Widget(h.widget_id()));
+
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 2);
+    });
+}
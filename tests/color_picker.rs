@@ -0,0 +1,96 @@
+mod harness;
+
+use druid::text::{Formatter, Selection};
+use druid::widget::Align;
+use druid::{Color, Event, UnitPoint, WidgetExt, WidgetId};
+use druid_gio_widgets::prelude::*;
+use druid_gio_widgets::widgets::color_picker::HexFormatter;
+use harness::mount;
+
+// the hue slider sits below the 200x150 square and a 10 pixel spacer
+const HUE_SLIDER: f64 = 150. + 10. + 7.;
+// the row of recent colors closes the editor
+const RECENT_SWATCH: f64 = 18.;
+
+fn rgba(color: &Color) -> (u8, u8, u8, u8) {
+    color.as_rgba8()
+}
+
+#[test]
+fn square_picks_saturation_and_value() {
+    mount(Color::rgb8(255, 0, 0), ColorEditor::new(), |h| {
+        h.click((100., 37.5));
+        assert_eq!(rgba(h.data()), (191, 96, 96, 255));
+    });
+}
+
+#[test]
+fn grays_keep_the_hue_of_the_slider() {
+    mount(Color::rgb8(255, 0, 0), ColorEditor::new(), |h| {
+        h.click((100., HUE_SLIDER));
+        assert_eq!(rgba(h.data()), (0, 255, 255, 255));
+
+        // white has no hue, going back to full saturation finds cyan again
+        h.click((0., 0.));
+        assert_eq!(rgba(h.data()), (255, 255, 255, 255));
+        h.click((199.99, 0.));
+        assert_eq!(rgba(h.data()), (0, 255, 255, 255));
+    });
+}
+
+#[test]
+fn swatch_opens_editor_popup() {
+    mount(Color::rgb8(255, 0, 0), ColorPicker::new(), |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+    });
+}
+
+#[test]
+fn hex_reads_alpha_only_when_allowed() {
+    let hex = HexFormatter::new();
+    let color = hex.value("#ff800080").unwrap();
+    assert_eq!(rgba(&color), (255, 128, 0, 128));
+    assert_eq!(hex.format(&color), "#ff800080");
+    assert_eq!(rgba(&hex.value(" f80 ").unwrap()), (255, 136, 0, 255));
+    assert!(hex.value("#ff80").is_ok());
+    assert!(hex.value("#ff80zz").is_err());
+
+    let opaque = HexFormatter::without_alpha();
+    assert!(opaque.value("#ff800080").is_err());
+    assert!(opaque.value("#f808").is_err());
+    assert_eq!(rgba(&opaque.value("#ff8000").unwrap()), (255, 128, 0, 255));
+    assert_eq!(opaque.format(&color), "#ff8000");
+    let typing = "#ff800080";
+    let caret = Selection::caret(typing.len());
+    assert!(opaque.validate_partial_input(typing, &caret).is_err());
+    assert!(!hex.validate_partial_input(typing, &caret).is_err());
+}
+
+#[test]
+fn editor_without_alpha_keeps_colors_opaque() {
+    let translucent = Color::rgba8(255, 0, 0, 128);
+    mount(translucent, ColorEditor::without_alpha(), |h| {
+        assert_eq!(rgba(h.data()), (255, 0, 0, 255));
+        h.click((100., 37.5));
+        assert_eq!(rgba(h.data()), (191, 96, 96, 255));
+    });
+}
+
+#[test]
+fn recent_colors_pick_again() {
+    mount(Color::BLACK, ColorEditor::new(), |h| {
+        h.click((100., 37.5));
+        // what closing the popup sends
+        h.event(Event::WindowDisconnected);
+    });
+
+    // editors opened since show the color in their last row
+    let id = WidgetId::next();
+    let editor = Align::new(UnitPoint::TOP_LEFT, ColorEditor::new().with_id(id));
+    mount(Color::BLACK, editor, |h| {
+        let bottom = h.layout_rect(id).height();
+        h.click((RECENT_SWATCH / 2., bottom - RECENT_SWATCH / 2.));
+        assert_eq!(rgba(h.data()), (191, 96, 96, 255));
+    });
+}
@@ -0,0 +1,87 @@
+mod harness;
+
+use druid::KbKey;
+use druid::Point;
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+const CELL: f64 = 28.;
+// the month and weekday rows above the days
+const GRID_TOP: f64 = 48.;
+
+fn date(year: i32, month: u8, day: u8) -> Date {
+    Date::new(year, month, day).unwrap()
+}
+
+/// The center of the `index`th day in the grid.
+fn cell(index: usize) -> Point {
+    Point::new(
+        ((index % 7) as f64 + 0.5) * CELL,
+        GRID_TOP + ((index / 7) as f64 + 0.5) * CELL,
+    )
+}
+
+#[test]
+fn date_arithmetic() {
+    assert_eq!(date(2024, 2, 1).weekday(), 3);
+    assert_eq!(date(2000, 1, 1).weekday(), 5);
+    assert_eq!(date(2024, 2, 28).add_days(1), date(2024, 2, 29));
+    assert_eq!(date(2023, 12, 31).add_days(1), date(2024, 1, 1));
+    assert_eq!(date(1969, 12, 31).add_days(1).to_string(), "1970-01-01");
+    assert_eq!(date(2024, 1, 31).add_months(1), date(2024, 2, 29));
+    assert_eq!(date(2024, 3, 15).add_months(-14), date(2023, 1, 15));
+    assert_eq!(Date::new(2023, 2, 29), None);
+}
+
+#[test]
+fn click_and_keys_pick_days() {
+    // February 2024 starts on a Thursday, the grid on Monday January 29th
+    mount(date(2024, 2, 10), Calendar::new(), |h| {
+        h.click(cell(16));
+        assert_eq!(*h.data(), date(2024, 2, 14));
+
+        // arrows only move the highlight, Enter picks
+        h.key_down(KbKey::ArrowRight);
+        assert_eq!(*h.data(), date(2024, 2, 14));
+        h.key_down(KbKey::Enter);
+        assert_eq!(*h.data(), date(2024, 2, 15));
+
+        h.key_down(KbKey::ArrowDown);
+        h.key_down(KbKey::PageDown);
+        h.key_down(KbKey::Enter);
+        assert_eq!(*h.data(), date(2024, 3, 22));
+    });
+}
+
+#[test]
+fn bounds_and_disabled_days_cannot_be_picked() {
+    let calendar = Calendar::new()
+        .with_max(date(2024, 2, 20))
+        .with_disabled(|date| date.weekday() >= 5);
+    mount(date(2024, 2, 10), calendar, |h| {
+        h.click(cell(19));
+        assert_eq!(*h.data(), date(2024, 2, 10));
+        h.click(cell(23));
+        assert_eq!(*h.data(), date(2024, 2, 10));
+        h.click(cell(18));
+        assert_eq!(*h.data(), date(2024, 2, 16));
+    });
+}
+
+#[test]
+fn arrows_move_between_months() {
+    mount(date(2024, 2, 10), Calendar::new(), |h| {
+        h.click((5.5 * CELL, CELL / 2.));
+        // March 1st is a Friday
+        h.click(cell(4));
+        assert_eq!(*h.data(), date(2024, 3, 1));
+    });
+}
+
+#[test]
+fn header_opens_calendar_popup() {
+    mount(date(2024, 2, 10), DatePicker::new(), |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+    });
+}
@@ -0,0 +1,81 @@
+mod harness;
+
+use druid::im::OrdSet;
+use druid::lens::Identity;
+use druid::widget::Label;
+use druid::{KbKey, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+const HEADER: f64 = 28.;
+
+fn sections(indices: &[usize]) -> OrdSet<usize> {
+    indices.iter().copied().collect()
+}
+
+fn accordion(accordion: Accordion<OrdSet<usize>>) -> Accordion<OrdSet<usize>> {
+    accordion
+        .with_section("First", Label::new("first body"))
+        .with_section("Second", Label::new("second body"))
+}
+
+#[test]
+fn header_folds_body_away() {
+    let body = Label::new("Body").on_click(|_, clicks: &mut u32, _| *clicks += 1);
+    mount(0, Disclosure::new("More", body), |h| {
+        // nothing shows under a closed header
+        h.click((10., HEADER + 5.));
+        assert_eq!(*h.data(), 0);
+
+        h.click((10., HEADER / 2.));
+        h.settle();
+        h.click((10., HEADER + 5.));
+        assert_eq!(*h.data(), 1);
+
+        // the header kept focus from the click
+        h.key_down(KbKey::Character(" ".into()));
+        h.settle();
+        h.click((10., HEADER + 5.));
+        assert_eq!(*h.data(), 1);
+    });
+}
+
+#[test]
+fn bound_section_stays_bound_when_expanded() {
+    let section = Disclosure::new("More", Label::new("Body"))
+        .bind_open(Identity)
+        .expanded();
+    mount(false, section, |h| {
+        h.click((10., HEADER / 2.));
+        assert!(*h.data());
+        h.click((10., HEADER / 2.));
+        assert!(!*h.data());
+    });
+}
+
+#[test]
+fn single_accordion_keeps_one_open() {
+    mount(OrdSet::new(), accordion(Accordion::single(Identity)), |h| {
+        h.click((10., HEADER * 1.5));
+        assert_eq!(*h.data(), sections(&[1]));
+        h.click((10., HEADER / 2.));
+        assert_eq!(*h.data(), sections(&[0]));
+        h.click((10., HEADER / 2.));
+        assert!(h.data().is_empty());
+    });
+}
+
+#[test]
+fn multiple_accordion_opens_each_on_its_own() {
+    mount(
+        OrdSet::new(),
+        accordion(Accordion::multiple(Identity)),
+        |h| {
+            h.click((10., HEADER * 1.5));
+            h.click((10., HEADER / 2.));
+            assert_eq!(*h.data(), sections(&[0, 1]));
+            h.click((10., HEADER / 2.));
+            assert_eq!(*h.data(), sections(&[1]));
+        },
+    );
+}
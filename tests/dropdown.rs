@@ -0,0 +1,57 @@
+mod harness;
+
+use druid::widget::{Button, Label};
+use druid::{EventCtx, Target, UnitPoint, Widget, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+fn dropdown() -> impl Widget<String> {
+    let header = Button::new("Select place")
+        .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(DROPDOWN_SHOW));
+    // only as wide as the header, leaving room beside it to click outside
+    Dropdown::new(header, |_, _| Label::new("popup")).align_horizontal(UnitPoint::TOP_LEFT)
+}
+
+#[test]
+fn header_click_opens_one_popup() {
+    mount(String::new(), dropdown(), |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+    });
+}
+
+#[test]
+fn click_outside_closes_popup() {
+    mount(String::new(), dropdown(), |h| {
+        h.click((5., 5.));
+        assert!(h.dismissed_windows().is_empty());
+
+        h.mouse_down((300., 300.));
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn hide_command_closes_popup() {
+    mount(String::new(), dropdown(), |h| {
+        h.click((5., 5.));
+        h.submit_command(DROPDOWN_HIDE.to(h.widget_id()));
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn popup_reopens_after_close() {
+    mount(String::new(), dropdown(), |h| {
+        h.click((5., 5.));
+        h.mouse_down((300., 300.));
+        let popup = h.dismissed_windows()[0];
+
+        h.submit_command(DROPDOWN_CLOSED.with(popup).to(Target::Global));
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 2);
+    });
+}
@@ -0,0 +1,327 @@
+//! Mounts a widget in druid's headless harness and records what it sends out of the tree.
+//!
+//! Painting goes to a CPU bitmap, so these tests don't need a display.
+
+#![allow(dead_code)]
+
+pub mod snapshot;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use druid::tests::harness::Harness;
+use druid::widget::prelude::*;
+use druid::{
+    Color, Command, InternalEvent, KbKey, KeyEvent, Modifiers, MouseButton, MouseButtons,
+    MouseEvent, Notification, Point, Rect, Selector, Target, Vec2, WidgetExt, WidgetPod, WindowId,
+};
+#[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+use druid_gio_widgets::widgets::popup::POPUP_DISMISS;
+
+pub const DEFAULT_SIZE: Size = Size::new(400., 400.);
+
+// mirrors druid's private `commands::NEW_SUB_WINDOW`, submitted by `EventCtx::new_sub_window`
+const NEW_SUB_WINDOW: Selector = Selector::new("druid-builtin.new-sub-window");
+// nobody handles this, submitting it just runs the queued commands
+const FLUSH: Selector = Selector::new("harness.flush");
+
+#[derive(Default)]
+struct Log {
+    commands: Vec<Command>,
+    notifications: Vec<Notification>,
+}
+
+/// Root of the mounted tree, sees every command routed through the window and every
+/// notification nobody else handled.
+struct Recorder<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    log: Rc<RefCell<Log>>,
+}
+
+impl<T: Data> Widget<T> for Recorder<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) | Event::Internal(InternalEvent::TargetedCommand(cmd)) => {
+                self.log.borrow_mut().commands.push(cmd.clone())
+            }
+            Event::Notification(notification) => {
+                self.log
+                    .borrow_mut()
+                    .notifications
+                    .push(notification.clone());
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env)
+    }
+}
+
+pub struct TestHarness<'a, 'b, T> {
+    inner: &'b mut Harness<'a, T>,
+    log: Rc<RefCell<Log>>,
+    widget: WidgetId,
+}
+
+/// Mounts `widget` with `data` in a window of [`DEFAULT_SIZE`], already connected and laid out.
+pub fn mount<T: Data>(
+    data: T,
+    widget: impl Widget<T> + 'static,
+    mut test: impl FnMut(&mut TestHarness<T>),
+) {
+    let (recorder, log, id) = recorder(widget);
+    Harness::create_simple(data, recorder, |harness| {
+        test(&mut TestHarness::connect(harness, log.clone(), id))
+    });
+}
+
+/// Like [`mount`], but paints the window once `test` returns and hands back the pixels.
+pub fn render<T: Data>(
+    data: T,
+    widget: impl Widget<T> + 'static,
+    size: Size,
+    mut test: impl FnMut(&mut TestHarness<T>),
+) -> Image {
+    let (recorder, log, id) = recorder(widget);
+    let mut image = None;
+    Harness::create_with_render(
+        data,
+        recorder,
+        size,
+        |harness| {
+            let mut harness = TestHarness::connect(harness, log.clone(), id);
+            test(&mut harness);
+            harness.paint();
+        },
+        |target| {
+            image = Some(Image {
+                width: size.width as usize,
+                height: size.height as usize,
+                pixels: target.into_raw(),
+            })
+        },
+    );
+    image.expect("harness did not render")
+}
+
+fn recorder<T: Data>(
+    widget: impl Widget<T> + 'static,
+) -> (Recorder<T>, Rc<RefCell<Log>>, WidgetId) {
+    let child = WidgetPod::new(widget.boxed());
+    let id = child.id();
+    let log = Rc::new(RefCell::new(Log::default()));
+    let recorder = Recorder {
+        child,
+        log: log.clone(),
+    };
+    (recorder, log, id)
+}
+
+impl<'a, 'b, T: Data> TestHarness<'a, 'b, T> {
+    fn connect(inner: &'b mut Harness<'a, T>, log: Rc<RefCell<Log>>, widget: WidgetId) -> Self {
+        inner.send_initial_events();
+        inner.just_layout();
+        TestHarness { inner, log, widget }
+    }
+
+    pub fn data(&self) -> &T {
+        self.inner.data()
+    }
+
+    /// The id of the mounted widget, commands sent to it reach its outermost controller.
+    pub fn widget_id(&self) -> WidgetId {
+        self.widget
+    }
+
+    pub fn event(&mut self, event: Event) {
+        self.inner.event(event);
+    }
+
+    /// Where `widget` was laid out, relative to its parent.
+    pub fn layout_rect(&mut self, widget: WidgetId) -> Rect {
+        self.inner.get_state(widget).layout_rect()
+    }
+
+    pub fn submit_command(&mut self, cmd: impl Into<Command>) {
+        self.inner.submit_command(cmd);
+    }
+
+    pub fn mouse_move(&mut self, pos: impl Into<Point>) {
+        self.event(Event::MouseMove(mouse(pos.into(), MouseButton::None, 0)));
+    }
+
+    pub fn mouse_down(&mut self, pos: impl Into<Point>) {
+        self.event(Event::MouseDown(mouse(pos.into(), MouseButton::Left, 1)));
+    }
+
+    pub fn mouse_up(&mut self, pos: impl Into<Point>) {
+        self.event(Event::MouseUp(mouse(pos.into(), MouseButton::Left, 0)));
+    }
+
+    /// Moves to `pos`, then presses and releases the left button there.
+    pub fn click(&mut self, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.mouse_move(pos);
+        self.mouse_down(pos);
+        self.mouse_up(pos);
+    }
+
+    /// Like [`click`](Self::click), holding `mods` down.
+    pub fn click_with(&mut self, mods: Modifiers, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.mouse_move(pos);
+        let mut down = mouse(pos, MouseButton::Left, 1);
+        down.mods = mods;
+        self.event(Event::MouseDown(down));
+        let mut up = mouse(pos, MouseButton::Left, 0);
+        up.mods = mods;
+        self.event(Event::MouseUp(up));
+    }
+
+    pub fn wheel(&mut self, pos: impl Into<Point>, delta: Vec2) {
+        let mut event = mouse(pos.into(), MouseButton::None, 0);
+        event.wheel_delta = delta;
+        self.event(Event::Wheel(event));
+    }
+
+    pub fn key_down(&mut self, key: KbKey) {
+        self.key_down_with(Modifiers::default(), key);
+    }
+
+    pub fn key_down_with(&mut self, mods: Modifiers, key: KbKey) {
+        self.event(Event::KeyDown(KeyEvent::for_test(mods, key)));
+    }
+
+    pub fn key_up(&mut self, key: KbKey) {
+        self.event(Event::KeyUp(KeyEvent::for_test(Modifiers::default(), key)));
+    }
+
+    /// Runs whatever animations are going to their end in one long frame.
+    pub fn settle(&mut self) {
+        self.event(Event::AnimFrame(Duration::from_secs(10).as_nanos() as u64));
+    }
+
+    pub fn layout(&mut self) {
+        self.inner.just_layout();
+    }
+
+    /// Lays out, runs what layout submitted and lays out again, for widgets that build
+    /// their children once they know their size.
+    pub fn relayout(&mut self) {
+        self.layout();
+        self.flush();
+        self.layout();
+    }
+
+    /// Runs the commands submitted outside of an event, from `update` or `layout`.
+    pub fn flush(&mut self) {
+        self.submit_command(FLUSH);
+    }
+
+    pub fn paint(&mut self) {
+        self.inner.paint();
+    }
+
+    /// Every command that was routed through the window since mounting, in order.
+    pub fn commands(&self) -> Vec<Command> {
+        self.log.borrow().commands.clone()
+    }
+
+    /// Commands matching `selector`, see [`TestHarness::commands`].
+    pub fn commands_of<S>(&self, selector: Selector<S>) -> Vec<Command> {
+        self.log
+            .borrow()
+            .commands
+            .iter()
+            .filter(|cmd| cmd.is(selector))
+            .cloned()
+            .collect()
+    }
+
+    /// Windows a `CLOSE_WINDOW` command was submitted for.
+    pub fn closed_windows(&self) -> Vec<WindowId> {
+        windows_of(self.commands_of(druid::commands::CLOSE_WINDOW))
+    }
+
+    /// Popup windows asked to play their exit animation and close.
+    #[cfg(any(feature = "dropdown", feature = "popover", feature = "select"))]
+    pub fn dismissed_windows(&self) -> Vec<WindowId> {
+        windows_of(self.commands_of(POPUP_DISMISS))
+    }
+
+    /// Notifications that bubbled up to the root unhandled.
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.log.borrow().notifications.clone()
+    }
+
+    /// How many sub-windows (popups, dropdowns) the tree asked for.
+    pub fn sub_windows_requested(&self) -> usize {
+        self.commands_of(NEW_SUB_WINDOW).len()
+    }
+
+    pub fn clear_log(&mut self) {
+        *self.log.borrow_mut() = Log::default();
+    }
+}
+
+fn windows_of(commands: Vec<Command>) -> Vec<WindowId> {
+    commands
+        .into_iter()
+        .filter_map(|cmd| match cmd.target() {
+            Target::Window(id) => Some(id),
+            _ => None,
+        })
+        .collect()
+}
+
+fn mouse(pos: Point, button: MouseButton, count: u8) -> MouseEvent {
+    let mut buttons = MouseButtons::new();
+    if count > 0 {
+        buttons.insert(button);
+    }
+    MouseEvent {
+        pos,
+        window_pos: pos,
+        buttons,
+        mods: Modifiers::default(),
+        count,
+        focus: false,
+        button,
+        wheel_delta: Vec2::ZERO,
+    }
+}
+
+/// Pixels painted by [`render`], premultiplied RGBA.
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Arc<[u8]>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 4;
+        let p = &self.pixels[i..i + 4];
+        Color::rgba8(p[0], p[1], p[2], p[3])
+    }
+}
@@ -0,0 +1,111 @@
+//! Compares rendered images against the PNGs checked in under `tests/snapshots`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to record missing references and overwrite the others
+//! with the current rendering, then commit them along with the test. Without it a missing
+//! reference fails the test.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use super::Image;
+
+/// Per channel difference still treated as equal, absorbs antialiasing noise.
+const CHANNEL_TOLERANCE: u8 = 3;
+/// Share of pixels allowed to differ beyond `CHANNEL_TOLERANCE`, absorbs font hinting.
+const PIXEL_TOLERANCE: f64 = 0.005;
+
+pub fn assert_snapshot(name: &str, image: &Image) {
+    let reference = snapshot_dir().join(format!("{name}.png"));
+    let actual = snapshot_dir().join(format!("{name}.actual.png"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        write_png(&reference, image);
+        eprintln!("recorded snapshot {}", reference.display());
+        return;
+    }
+    if !reference.exists() {
+        write_png(&actual, image);
+        panic!(
+            "snapshot `{name}` has no reference at {}, rendering saved to {}; \
+             rerun with UPDATE_SNAPSHOTS=1 to record it",
+            reference.display(),
+            actual.display(),
+        );
+    }
+
+    let expected = read_png(&reference);
+    let size_matches = (expected.width, expected.height) == (image.width, image.height);
+    let differing = if size_matches {
+        expected
+            .pixels
+            .chunks_exact(4)
+            .zip(image.pixels.chunks_exact(4))
+            .filter(|(a, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+            })
+            .count()
+    } else {
+        usize::MAX
+    };
+
+    let allowed = (image.width * image.height) as f64 * PIXEL_TOLERANCE;
+    if differing as f64 > allowed {
+        write_png(&actual, image);
+        panic!(
+            "snapshot `{name}` differs from {} ({}), rendering saved to {}",
+            reference.display(),
+            if size_matches {
+                format!("{differing} pixels")
+            } else {
+                format!(
+                    "{}x{} vs {}x{}",
+                    image.width, image.height, expected.width, expected.height
+                )
+            },
+            actual.display(),
+        );
+    }
+    let _ = std::fs::remove_file(actual);
+}
+
+fn snapshot_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+}
+
+// the pixels are stored as rendered, premultiplied, which only matters for translucent ones
+fn write_png(path: &Path, image: &Image) {
+    std::fs::create_dir_all(path.parent().unwrap()).expect("create snapshot dir");
+    let file = File::create(path).expect("create snapshot");
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        image.width as u32,
+        image.height as u32,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("write png header");
+    writer
+        .write_image_data(&image.pixels)
+        .expect("write png data");
+}
+
+fn read_png(path: &Path) -> Image {
+    let file = File::open(path).expect("open snapshot");
+    let mut reader = png::Decoder::new(BufReader::new(file))
+        .read_info()
+        .expect("read png header");
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).expect("read png data");
+    assert_eq!(info.color_type, png::ColorType::Rgba, "snapshots are RGBA");
+    pixels.truncate(info.buffer_size());
+    Image {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels: pixels.into(),
+    }
+}
@@ -0,0 +1,122 @@
+mod harness;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use druid::widget::prelude::*;
+use druid::widget::{Controller, Flex, Label};
+use druid::{Command, Selector, WidgetExt};
+use druid_gio_widgets::{match_command, selectors};
+use harness::mount;
+
+const ECHO: Selector<i32> = Selector::new("tests.macros.echo");
+const UNKNOWN: Selector = Selector::new("tests.macros.unknown");
+
+selectors! {
+    DOUBLE: i32,
+    RESET,
+}
+
+fn doubled(cmd: &Command) -> i32 {
+    match_command!(cmd => {
+        DOUBLE(i) if *i > 0 => i * 2,
+        DOUBLE(_) => 0,
+        RESET => -1,
+        _ => -2,
+    })
+}
+
+// Stands in for a widget further down the tree, sending an echo back up as a notification.
+struct Echo;
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Echo {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event
+            && let Some(value) = cmd.get(ECHO)
+        {
+            ctx.submit_notification(DOUBLE.with(*value));
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+// Writes down what its arms matched, positive commands doubled and positive notifications as is.
+struct Matcher(Rc<RefCell<Vec<i32>>>);
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Matcher {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match_command!(event, ctx => {
+            Command(DOUBLE(i)) if *i > 0 => self.0.borrow_mut().push(i * 2),
+            Notification(DOUBLE(i)) if *i > 0 => {
+                self.0.borrow_mut().push(*i);
+            }
+        });
+        child.event(ctx, event, data, env)
+    }
+}
+
+// Writes down every `DOUBLE` command that reaches the widget.
+struct Spy(Rc<RefCell<Vec<i32>>>);
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Spy {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event
+            && let Some(value) = cmd.get(DOUBLE)
+        {
+            self.0.borrow_mut().push(*value);
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+#[test]
+fn arms_bind_the_payload_and_test_the_guard() {
+    assert_eq!(doubled(&DOUBLE.with(3)), 6);
+    // a failing guard moves on to the next arm
+    assert_eq!(doubled(&DOUBLE.with(-3)), 0);
+    assert_eq!(doubled(&RESET.into()), -1);
+}
+
+#[test]
+fn fallback_takes_what_no_arm_matched() {
+    assert_eq!(doubled(&UNKNOWN.into()), -2);
+
+    let mut seen = None;
+    let () = match_command!(Command::from(UNKNOWN) => { DOUBLE(i) => seen = Some(*i) });
+    assert_eq!(seen, None);
+    match_command!(DOUBLE.with(4) => { DOUBLE(i) => seen = Some(*i) });
+    assert_eq!(seen, Some(4));
+}
+
+#[test]
+fn matched_event_arms_set_handled() {
+    let matched = Rc::new(RefCell::new(Vec::new()));
+    let spied = Rc::new(RefCell::new(Vec::new()));
+    let widget = Flex::column()
+        .with_child(
+            Label::new("Matcher")
+                .controller(Echo)
+                .padding(0.)
+                .controller(Matcher(matched.clone())),
+        )
+        .with_child(Label::new("Spy").controller(Spy(spied.clone())));
+    mount((), widget, |h| {
+        h.submit_command(DOUBLE.with(2));
+        assert_eq!(*matched.borrow(), [4]);
+        // handled by the matcher, so its sibling never sees it
+        assert!(spied.borrow().is_empty());
+
+        h.submit_command(DOUBLE.with(-1));
+        assert_eq!(*matched.borrow(), [4]);
+        assert_eq!(*spied.borrow(), [-1]);
+
+        h.submit_command(ECHO.with(5));
+        assert_eq!(*matched.borrow(), [4, 5]);
+        assert!(h.notifications().is_empty());
+
+        // the guard turns this one away, so it bubbles on past the matcher
+        h.submit_command(ECHO.with(-5));
+        assert_eq!(*matched.borrow(), [4, 5]);
+        assert_eq!(h.notifications().len(), 1);
+    });
+}
@@ -0,0 +1,82 @@
+mod harness;
+
+use druid::Vec2;
+use druid::text::Formatter;
+use druid_gio_widgets::prelude::*;
+use druid_gio_widgets::widgets::number_input::NumberFormatter;
+use harness::mount;
+
+// the row keeps its own width at the left of the window, a 100 pixel field between two
+// 33 pixel buttons, centered on the window's height
+const MINUS: (f64, f64) = (16., 200.);
+const PLUS: (f64, f64) = (150., 200.);
+const FIELD: (f64, f64) = (83., 200.);
+
+fn read<T: NumberValue>(formatter: &NumberFormatter, text: &str) -> Option<T> {
+    Formatter::<T>::value(formatter, text).ok()
+}
+
+#[test]
+fn buttons_step_within_range() {
+    let input = NumberInput::new().with_range(0., 2.).with_step(0.5);
+    mount(1.5, input, |h| {
+        h.click(PLUS);
+        assert_eq!(*h.data(), 2.);
+        h.click(PLUS);
+        assert_eq!(*h.data(), 2.);
+
+        for _ in 0..5 {
+            h.click(MINUS);
+        }
+        assert_eq!(*h.data(), 0.);
+    });
+}
+
+#[test]
+fn wheel_steps_integers() {
+    let input = NumberInput::new().with_step(2.);
+    mount(3i64, input, |h| {
+        h.wheel(FIELD, Vec2::new(0., -10.));
+        assert_eq!(*h.data(), 5);
+        h.wheel(FIELD, Vec2::new(0., 10.));
+        h.wheel(FIELD, Vec2::new(0., 10.));
+        assert_eq!(*h.data(), 1);
+    });
+}
+
+#[test]
+fn step_while_editing_is_not_lost() {
+    let input = NumberInput::new().with_precision(1).with_unit("ms");
+    mount(1.5, input, |h| {
+        h.click(FIELD);
+        h.click(PLUS);
+        assert_eq!(*h.data(), 2.5);
+    });
+}
+
+#[test]
+fn wheel_while_editing_is_not_lost() {
+    let input = NumberInput::new().with_precision(1).with_unit("ms");
+    mount(1.5, input, |h| {
+        h.click(FIELD);
+        h.wheel(FIELD, Vec2::new(0., -10.));
+        assert_eq!(*h.data(), 2.5);
+    });
+}
+
+#[test]
+fn garbage_and_out_of_range_text_make_no_value() {
+    let formatter = NumberInput::<f64>::new()
+        .with_range(0., 10.)
+        .with_unit("ms")
+        .formatter();
+    assert_eq!(read::<f64>(&formatter, "2.5 ms"), Some(2.5));
+    assert_eq!(read::<f64>(&formatter, " 2.5"), Some(2.5));
+    assert_eq!(read::<f64>(&formatter, "2.5 s"), None);
+    assert_eq!(read::<f64>(&formatter, "abc"), None);
+    assert_eq!(read::<f64>(&formatter, ""), None);
+    assert_eq!(read::<f64>(&formatter, "11"), None);
+    assert_eq!(read::<f64>(&formatter, "inf"), None);
+    assert_eq!(read::<i64>(&formatter, "2.5"), None);
+    assert_eq!(read::<i64>(&formatter, "2"), Some(2));
+}
@@ -0,0 +1,84 @@
+mod harness;
+
+use druid::widget::{Button, Label, TextBox};
+use druid::{Target, Widget};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+fn popover(trigger: PopoverTrigger) -> impl Widget<String> {
+    Popover::new(Button::new("Info"), |_, _| Label::new("popover")).with_trigger(trigger)
+}
+
+#[test]
+fn click_toggles_popover() {
+    mount(String::new(), popover(PopoverTrigger::Click), |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+        assert!(h.dismissed_windows().is_empty());
+
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn click_outside_closes_popover() {
+    mount(String::new(), popover(PopoverTrigger::Click), |h| {
+        h.click((5., 5.));
+        h.mouse_down((300., 300.));
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn hover_opens_popover() {
+    mount(String::new(), popover(PopoverTrigger::Hover), |h| {
+        h.mouse_move((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+        assert!(h.dismissed_windows().is_empty());
+    });
+}
+
+#[test]
+fn focus_opens_popover() {
+    let anchor =
+        Popover::new(TextBox::new(), |_, _| Label::new("hint")).with_trigger(PopoverTrigger::Focus);
+    mount(String::new(), anchor, |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+
+        // still focused, so no second popover
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+    });
+}
+
+#[test]
+fn manual_popover_ignores_clicks() {
+    mount(String::new(), popover(PopoverTrigger::Manual), |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 0);
+
+        h.submit_command(POPOVER_SHOW.to(h.widget_id()));
+        assert_eq!(h.sub_windows_requested(), 1);
+        h.submit_command(POPOVER_HIDE.to(h.widget_id()));
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn reopens_after_close() {
+    mount(String::new(), popover(PopoverTrigger::Click), |h| {
+        h.click((5., 5.));
+        h.mouse_down((300., 300.));
+        let popup = h.dismissed_windows()[0];
+
+        h.submit_command(POPOVER_CLOSED.with(popup).to(Target::Global));
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 2);
+    });
+}
@@ -0,0 +1,70 @@
+mod harness;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use druid::Target;
+use druid::widget::Label;
+use druid::{Event, Widget};
+use druid_gio_widgets::animation::set_reduced_motion;
+use druid_gio_widgets::widgets::popup::{
+    POPUP_DISMISS, PopupAnimation, PopupRoot, PopupTransition,
+};
+use harness::mount;
+
+const FRAME: u64 = 60_000_000;
+
+// reduced motion is global, tests touching it must not overlap
+static MOTION: Mutex<()> = Mutex::new(());
+
+fn popup(transition: PopupTransition) -> impl Widget<()> {
+    PopupRoot::new(Label::new("popup"), transition)
+}
+
+fn fade() -> PopupTransition {
+    PopupTransition::new(PopupAnimation::Fade).with_duration(Duration::from_nanos(2 * FRAME))
+}
+
+#[test]
+fn closes_once_exit_animation_ends() {
+    let _motion = MOTION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    mount((), popup(fade()), |h| {
+        // let the enter animation finish
+        h.event(Event::AnimFrame(2 * FRAME));
+
+        h.submit_command(POPUP_DISMISS.to(Target::Global));
+        assert!(h.closed_windows().is_empty());
+
+        h.event(Event::AnimFrame(FRAME));
+        assert!(h.closed_windows().is_empty());
+
+        h.event(Event::AnimFrame(FRAME));
+        assert_eq!(h.closed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn closes_at_once_without_animation() {
+    let _motion = MOTION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    mount((), popup(PopupTransition::NONE), |h| {
+        h.submit_command(POPUP_DISMISS.to(Target::Global));
+        assert_eq!(h.closed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn reduced_motion_skips_animation() {
+    let _motion = MOTION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    set_reduced_motion(true);
+    mount((), popup(fade()), |h| {
+        h.submit_command(POPUP_DISMISS.to(Target::Global));
+        assert_eq!(h.closed_windows().len(), 1);
+    });
+    set_reduced_motion(false);
+}
@@ -0,0 +1,46 @@
+mod harness;
+
+use druid::widget::Button;
+use druid::{EventCtx, Target, UnitPoint, Widget, WidgetExt, WindowId};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+fn select() -> impl Widget<String> {
+    let header = Button::new("Select place")
+        .on_click(|ctx: &mut EventCtx, _, _| ctx.submit_notification(SELECT_SHOW));
+    // only as wide as the header, leaving room beside it to click outside
+    Select::new(header, ["England", "Antarctica"]).align_horizontal(UnitPoint::TOP_LEFT)
+}
+
+#[test]
+fn header_click_opens_one_popup() {
+    mount(String::new(), select(), |h| {
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+
+        h.click((5., 5.));
+        assert_eq!(h.sub_windows_requested(), 1);
+    });
+}
+
+#[test]
+fn click_outside_closes_popup() {
+    mount(String::new(), select(), |h| {
+        h.click((5., 5.));
+        h.mouse_down((300., 300.));
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
+
+#[test]
+fn closing_another_popup_is_ignored() {
+    mount(String::new(), select(), |h| {
+        h.click((5., 5.));
+        let other = WindowId::next();
+        h.submit_command(SELECT_CLOSED.with(other).to(Target::Global));
+
+        // still open, so a click outside has a window to close
+        h.mouse_down((300., 300.));
+        assert_eq!(h.dismissed_windows().len(), 1);
+    });
+}
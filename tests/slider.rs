@@ -0,0 +1,74 @@
+mod harness;
+
+use druid::KbKey;
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+// the window is 400 wide, thumb centers run from 8 to 392
+fn x_of(fraction: f64) -> (f64, f64) {
+    (8. + 384. * fraction, 200.)
+}
+
+#[test]
+fn click_and_keys_move_thumb() {
+    let slider = Slider::new().with_range(0., 100.);
+    mount(0., slider, |h| {
+        h.click(x_of(0.25));
+        assert_eq!(*h.data(), 25.);
+
+        h.key_down(KbKey::ArrowRight);
+        assert_eq!(*h.data(), 26.);
+        h.key_down(KbKey::PageUp);
+        assert_eq!(*h.data(), 36.);
+        h.key_down(KbKey::Home);
+        assert_eq!(*h.data(), 0.);
+        h.key_down(KbKey::ArrowLeft);
+        assert_eq!(*h.data(), 0.);
+        h.key_down(KbKey::End);
+        assert_eq!(*h.data(), 100.);
+    });
+}
+
+#[test]
+fn steps_snap_values() {
+    let slider = Slider::new().with_range(0., 100.).with_step(10.);
+    mount(0., slider, |h| {
+        h.click(x_of(0.27));
+        assert_eq!(*h.data(), 30.);
+        h.key_down(KbKey::ArrowRight);
+        assert_eq!(*h.data(), 40.);
+    });
+}
+
+#[test]
+fn vertical_runs_bottom_to_top() {
+    let slider = Slider::new().with_range(0., 100.).vertical();
+    mount(0., slider, |h| {
+        h.click((200., 392. - 384. * 0.25));
+        assert_eq!(*h.data(), 25.);
+        h.key_down(KbKey::ArrowUp);
+        assert_eq!(*h.data(), 26.);
+    });
+}
+
+#[test]
+fn range_thumbs_do_not_cross() {
+    let slider = Slider::new().with_range(0., 100.).with_step(10.);
+    mount((20., 80.), slider, |h| {
+        // the press grabs the nearest thumb
+        h.click(x_of(0.7));
+        assert_eq!(*h.data(), (20., 70.));
+        h.click(x_of(0.1));
+        assert_eq!(*h.data(), (10., 70.));
+
+        h.mouse_move(x_of(0.1));
+        h.mouse_down(x_of(0.1));
+        h.mouse_move(x_of(0.9));
+        h.mouse_up(x_of(0.9));
+        assert_eq!(*h.data(), (70., 70.));
+
+        // keys move the thumb pressed last
+        h.key_down(KbKey::ArrowLeft);
+        assert_eq!(*h.data(), (60., 70.));
+    });
+}
@@ -0,0 +1,83 @@
+mod harness;
+
+use druid::widget::prelude::*;
+use druid::widget::{Button, Controller, Label};
+use druid::{Point, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::render;
+use harness::snapshot::assert_snapshot;
+
+const BUTTON_SIZE: Size = Size::new(160., 40.);
+const CENTER: Point = Point::new(80., 20.);
+
+// Takes focus as soon as the window is connected.
+struct TakeFocus;
+
+impl<T, W: Widget<T>> Controller<T, W> for TakeFocus {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::WindowConnected = event {
+            ctx.request_focus();
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+fn button() -> AdvancedButton<()> {
+    AdvancedButton::new("Advanced Button")
+}
+
+#[test]
+fn advanced_button_normal() {
+    let image = render((), button(), BUTTON_SIZE, |_| {});
+    assert_snapshot("advanced_button_normal", &image);
+}
+
+#[test]
+fn advanced_button_hot() {
+    let image = render((), button(), BUTTON_SIZE, |h| {
+        h.mouse_move(CENTER);
+        h.settle();
+    });
+    assert_snapshot("advanced_button_hot", &image);
+}
+
+#[test]
+fn advanced_button_active() {
+    let image = render((), button(), BUTTON_SIZE, |h| {
+        h.mouse_move(CENTER);
+        h.mouse_down(CENTER);
+        h.settle();
+    });
+    assert_snapshot("advanced_button_active", &image);
+}
+
+#[test]
+fn advanced_button_focused() {
+    let image = render((), button().controller(TakeFocus), BUTTON_SIZE, |h| {
+        h.settle()
+    });
+    assert_snapshot("advanced_button_focused", &image);
+}
+
+#[test]
+fn advanced_button_disabled() {
+    let image = render((), button().disabled_if(|_, _| true), BUTTON_SIZE, |h| {
+        h.settle()
+    });
+    assert_snapshot("advanced_button_disabled", &image);
+}
+
+#[test]
+fn dropdown_header() {
+    let header = Button::new("Select place");
+    let dropdown = Dropdown::new(header, |_, _| Label::new("popup"));
+    let image = render(String::new(), dropdown, BUTTON_SIZE, |_| {});
+    assert_snapshot("dropdown_header", &image);
+}
+
+#[test]
+fn select_header() {
+    let select = Select::new(Button::new("Select place"), ["England"]);
+    let image = render(String::new(), select, BUTTON_SIZE, |_| {});
+    assert_snapshot("select_header", &image);
+}
@@ -0,0 +1,204 @@
+//! Reads the color scheme from a stand-in settings portal on a private bus.
+
+#![cfg(target_os = "linux")]
+
+use std::cell::Cell;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use druid_gio_widgets::theme::Preset;
+use druid_gio_widgets::theme::system::{ColorScheme, Portal};
+use gio::glib::{MainContext, ToVariant, Variant};
+use gio::{Cancellable, DBusCallFlags, DBusConnection, DBusConnectionFlags, DBusNodeInfo};
+
+const PORTAL: &str = "org.freedesktop.portal.Desktop";
+const PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS: &str = "org.freedesktop.portal.Settings";
+const SETTINGS_XML: &str = r#"
+<node>
+  <interface name="org.freedesktop.portal.Settings">
+    <method name="ReadOne">
+      <arg type="s" direction="in"/>
+      <arg type="s" direction="in"/>
+      <arg type="v" direction="out"/>
+    </method>
+    <signal name="SettingChanged">
+      <arg type="s"/>
+      <arg type="s"/>
+      <arg type="v"/>
+    </signal>
+  </interface>
+</node>
+"#;
+const PREFER_DARK: u32 = 1;
+const PREFER_LIGHT: u32 = 2;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A private message bus, killed on drop.
+struct Bus {
+    daemon: Child,
+    address: String,
+}
+
+impl Bus {
+    fn start() -> Bus {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("these tests need dbus-daemon installed");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .expect("read the address of the test bus");
+        Bus {
+            daemon,
+            address: address.trim().to_owned(),
+        }
+    }
+
+    fn connect(&self) -> DBusConnection {
+        DBusConnection::for_address_sync(
+            &self.address,
+            DBusConnectionFlags::AUTHENTICATION_CLIENT
+                | DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+            None,
+            Cancellable::NONE,
+        )
+        .expect("connect to the test bus")
+    }
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+fn boxed(value: u32) -> Variant {
+    Variant::from_variant(&value.to_variant())
+}
+
+/// Serves `color-scheme` like xdg-desktop-portal does, from a thread of its own so the
+/// test thread can block on calls. Sending a value changes the setting.
+fn serve_portal(bus: &Bus, scheme: u32) -> mpsc::Sender<u32> {
+    let connection = bus.connect();
+    let (changes, changed) = mpsc::channel::<u32>();
+    let (ready, is_ready) = mpsc::channel();
+    thread::spawn(move || {
+        let context = MainContext::new();
+        context
+            .with_thread_default(|| {
+                let current = Arc::new(AtomicU32::new(scheme));
+                let node = DBusNodeInfo::for_xml(SETTINGS_XML).unwrap();
+                let reading = current.clone();
+                connection
+                    .register_object(
+                        PATH,
+                        &node.lookup_interface(SETTINGS).unwrap(),
+                        move |_, _, _, _, _, _, invocation| {
+                            let value = boxed(reading.load(Ordering::SeqCst));
+                            invocation.return_value(Some(&Variant::tuple_from_iter([value])));
+                        },
+                        |_, _, _, _, _| unreachable!("the portal has no properties"),
+                        |_, _, _, _, _, _| false,
+                    )
+                    .unwrap();
+                connection
+                    .call_sync(
+                        Some("org.freedesktop.DBus"),
+                        "/org/freedesktop/DBus",
+                        "org.freedesktop.DBus",
+                        "RequestName",
+                        Some(&(PORTAL, 4u32).to_variant()),
+                        None,
+                        DBusCallFlags::NONE,
+                        -1,
+                        Cancellable::NONE,
+                    )
+                    .unwrap();
+                ready.send(()).unwrap();
+
+                loop {
+                    context.iteration(false);
+                    match changed.try_recv() {
+                        Ok(scheme) => {
+                            current.store(scheme, Ordering::SeqCst);
+                            let params = Variant::tuple_from_iter([
+                                "org.freedesktop.appearance".to_variant(),
+                                "color-scheme".to_variant(),
+                                boxed(scheme),
+                            ]);
+                            connection
+                                .emit_signal(None, PATH, SETTINGS, "SettingChanged", Some(&params))
+                                .unwrap();
+                        }
+                        Err(mpsc::TryRecvError::Empty) => thread::sleep(Duration::from_millis(5)),
+                        Err(mpsc::TryRecvError::Disconnected) => break,
+                    }
+                }
+            })
+            .unwrap();
+    });
+    is_ready.recv_timeout(TIMEOUT).expect("portal stand-in up");
+    changes
+}
+
+#[test]
+#[ignore = "starts a dbus-daemon, run with --ignored where one is installed"]
+fn reads_portal_color_scheme() {
+    let bus = Bus::start();
+    let _portal = serve_portal(&bus, PREFER_DARK);
+
+    let scheme = Portal::on(bus.connect()).color_scheme();
+    assert_eq!(scheme, Some(ColorScheme::Dark));
+    assert_eq!(ColorScheme::Dark.preset(Preset::Light), Preset::Dark);
+}
+
+#[test]
+#[ignore = "starts a dbus-daemon, run with --ignored where one is installed"]
+fn reports_nothing_without_portal() {
+    let bus = Bus::start();
+    assert_eq!(Portal::on(bus.connect()).color_scheme(), None);
+    assert_eq!(
+        ColorScheme::NoPreference.preset(Preset::Light),
+        Preset::Light
+    );
+}
+
+#[test]
+#[ignore = "starts a dbus-daemon, run with --ignored where one is installed"]
+fn follows_portal_changes() {
+    let bus = Bus::start();
+    let portal_changes = serve_portal(&bus, PREFER_DARK);
+
+    // signal callbacks run on the thread-default context of whoever subscribed
+    let context = MainContext::new();
+    context
+        .with_thread_default(|| {
+            let portal = Portal::on(bus.connect());
+            let seen = Rc::new(Cell::new(None));
+            let _watch = portal.watch({
+                let seen = seen.clone();
+                move |scheme| seen.set(Some(scheme))
+            });
+            // a round trip makes sure the bus has our match rule before the change
+            assert_eq!(portal.color_scheme(), Some(ColorScheme::Dark));
+
+            portal_changes.send(PREFER_LIGHT).unwrap();
+            let start = Instant::now();
+            while seen.get().is_none() && start.elapsed() < TIMEOUT {
+                context.iteration(false);
+                thread::sleep(Duration::from_millis(5));
+            }
+            assert_eq!(seen.get(), Some(ColorScheme::Light));
+            assert_eq!(portal.color_scheme(), Some(ColorScheme::Light));
+        })
+        .unwrap();
+}
@@ -0,0 +1,220 @@
+mod harness;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use druid::im::{OrdSet, Vector};
+use druid::widget::prelude::*;
+use druid::widget::{Controller, Label};
+use druid::{KbKey, Modifiers, Selector, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+const HEADER: f64 = 14.;
+const ROW: f64 = 20.;
+// rows start below the 28 pixel header
+const FIRST_ROW: f64 = 28. + ROW / 2.;
+const REMOVE: Selector<usize> = Selector::new("tests.table.remove");
+
+#[derive(Debug, Clone, Data, PartialEq)]
+struct File {
+    name: String,
+    size: u32,
+}
+
+fn files(files: &[(&str, u32)]) -> TableState<File> {
+    TableState::new(
+        files
+            .iter()
+            .map(|&(name, size)| File {
+                name: name.to_owned(),
+                size,
+            })
+            .collect(),
+    )
+}
+
+// Stands in for the part of the app deleting rows.
+struct Remove;
+
+impl<W: Widget<TableState<File>>> Controller<TableState<File>, W> for Remove {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TableState<File>,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event
+            && let Some(index) = cmd.get(REMOVE)
+        {
+            data.rows.remove(*index);
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+fn table(built: &Rc<Cell<usize>>) -> Table<File> {
+    let cell = move |text: fn(&File) -> String| {
+        let built = built.clone();
+        move || {
+            built.set(built.get() + 1);
+            Label::new(move |file: &File, _: &Env| text(file))
+        }
+    };
+    Table::new(ROW)
+        .with_column(
+            Column::new("Name", cell(|file| file.name.clone()), 100.)
+                .sortable_by_key(|file: &File| file.name.clone()),
+        )
+        .with_column(
+            Column::new("Size", cell(|file| file.size.to_string()), 100.)
+                .sortable_by_key(|file: &File| file.size),
+        )
+}
+
+fn selected(indices: &[usize]) -> OrdSet<usize> {
+    indices.iter().copied().collect()
+}
+
+#[test]
+fn header_click_sorts_and_flips() {
+    mount(
+        files(&[("b", 2), ("a", 2), ("c", 1)]),
+        table(&Rc::default()),
+        |h| {
+            h.click((50., HEADER));
+            h.click((50., FIRST_ROW));
+            assert_eq!(h.data().selected, selected(&[1]));
+
+            h.click((50., HEADER));
+            assert!(!h.data().sort[0].ascending);
+            h.click((50., FIRST_ROW));
+            assert_eq!(h.data().selected, selected(&[2]));
+        },
+    );
+}
+
+#[test]
+fn shift_click_adds_sort_column() {
+    mount(
+        files(&[("b", 2), ("a", 2), ("c", 1)]),
+        table(&Rc::default()),
+        |h| {
+            // by size alone "b" stays ahead of "a", the name breaks the tie
+            h.click((150., HEADER));
+            h.click_with(Modifiers::SHIFT, (50., HEADER));
+            assert_eq!(h.data().sort.len(), 2);
+
+            h.click((50., FIRST_ROW + ROW));
+            assert_eq!(h.data().selected_rows().next().unwrap().name, "a");
+            h.click((50., FIRST_ROW + 2. * ROW));
+            assert_eq!(h.data().selected_rows().next().unwrap().name, "b");
+        },
+    );
+}
+
+#[test]
+fn dragging_header_edge_resizes_column() {
+    mount(files(&[("a", 1)]), table(&Rc::default()), |h| {
+        h.mouse_move((100., HEADER));
+        h.mouse_down((100., HEADER));
+        h.mouse_move((150., HEADER));
+        h.mouse_up((150., HEADER));
+        assert!(h.data().sort.is_empty());
+
+        // the name column now reaches past 120
+        h.click((120., HEADER));
+        assert_eq!(h.data().sort[0].column, 0);
+    });
+}
+
+#[test]
+fn ctrl_and_shift_extend_selection() {
+    let table = table(&Rc::default()).with_selection(SelectionMode::Multi);
+    let names: Vec<_> = (0..10).map(|index| (["a", "b"][index % 2], 0)).collect();
+    mount(files(&names), table, |h| {
+        h.click((50., FIRST_ROW));
+        h.click_with(Modifiers::CONTROL, (50., FIRST_ROW + 2. * ROW));
+        assert_eq!(h.data().selected, selected(&[0, 2]));
+
+        h.click_with(Modifiers::SHIFT, (50., FIRST_ROW + 4. * ROW));
+        assert_eq!(h.data().selected, selected(&[2, 3, 4]));
+    });
+}
+
+#[test]
+fn shift_arrows_grow_the_range_from_the_anchor() {
+    let table = table(&Rc::default()).with_selection(SelectionMode::Multi);
+    let names: Vec<_> = (0..10).map(|index| (["a", "b"][index % 2], 0)).collect();
+    mount(files(&names), table, |h| {
+        h.click((50., FIRST_ROW + 2. * ROW));
+        h.key_down_with(Modifiers::SHIFT, KbKey::ArrowDown);
+        h.key_down_with(Modifiers::SHIFT, KbKey::ArrowDown);
+        assert_eq!(h.data().selected, selected(&[2, 3, 4]));
+
+        // back past the anchor the range flips around it
+        h.key_down_with(Modifiers::SHIFT, KbKey::ArrowUp);
+        h.key_down_with(Modifiers::SHIFT, KbKey::ArrowUp);
+        h.key_down_with(Modifiers::SHIFT, KbKey::ArrowUp);
+        assert_eq!(h.data().selected, selected(&[1, 2]));
+    });
+}
+
+#[test]
+fn arrows_move_from_the_last_row_shift_clicked() {
+    let table = table(&Rc::default()).with_selection(SelectionMode::Multi);
+    let names: Vec<_> = (0..10).map(|index| (["a", "b"][index % 2], 0)).collect();
+    mount(files(&names), table, |h| {
+        h.click((50., FIRST_ROW + ROW));
+        h.click_with(Modifiers::SHIFT, (50., FIRST_ROW + 4. * ROW));
+        assert_eq!(h.data().selected, selected(&[1, 2, 3, 4]));
+
+        h.key_down(KbKey::ArrowDown);
+        assert_eq!(h.data().selected, selected(&[5]));
+        h.key_down_with(Modifiers::SHIFT, KbKey::ArrowUp);
+        assert_eq!(h.data().selected, selected(&[4, 5]));
+    });
+}
+
+#[test]
+fn builds_cells_of_visible_rows_only() {
+    let built = Rc::new(Cell::new(0));
+    let rows: Vector<_> = (0..100_000)
+        .map(|size| File {
+            name: String::new(),
+            size,
+        })
+        .collect();
+    mount(TableState::new(rows), table(&built), |h| {
+        h.relayout();
+        // two columns of the 19 rows under the header, plus a few on each side
+        assert!(built.get() < 2 * 30, "built {} cells", built.get());
+    });
+}
+
+#[test]
+fn selection_follows_rows_when_one_is_removed() {
+    let table = table(&Rc::default()).with_selection(SelectionMode::Multi);
+    mount(
+        files(&[("a", 1), ("b", 2), ("c", 3), ("d", 4)]),
+        table.controller(Remove),
+        |h| {
+            h.click((50., FIRST_ROW + ROW));
+            h.click_with(Modifiers::CONTROL, (50., FIRST_ROW + 3. * ROW));
+            assert_eq!(h.data().selected, selected(&[1, 3]));
+
+            h.submit_command(REMOVE.with(0));
+            // the table moves the selection with a command to itself
+            h.flush();
+            assert_eq!(h.data().selected, selected(&[0, 2]));
+            let names: Vec<_> = h.data().selected_rows().map(|file| &file.name).collect();
+            assert_eq!(names, ["b", "d"]);
+
+            h.submit_command(REMOVE.with(0));
+            h.flush();
+            assert_eq!(h.data().selected, selected(&[1]));
+        },
+    );
+}
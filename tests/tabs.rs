@@ -0,0 +1,130 @@
+mod harness;
+
+use druid::widget::prelude::*;
+use druid::widget::{Controller, Label};
+use druid::{KbKey, Modifiers, Selector, Vec2, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+// labels this short leave every tab at its minimum width
+const TAB: f64 = 80.;
+const BAR: f64 = 15.;
+const CLOSE: Selector<usize> = Selector::new("tests.tabs.close");
+
+fn tabs() -> Tabs<String> {
+    Tabs::new(
+        |tab: &String| tab.clone(),
+        |_, _| Label::new(|tab: &String, _: &Env| tab.clone()),
+    )
+}
+
+fn state(tabs: &[&str]) -> TabsState<String> {
+    TabsState::new(tabs.iter().map(|tab| tab.to_string()).collect())
+}
+
+// Stands in for the part of the app closing tabs.
+struct Close;
+
+impl<W: Widget<TabsState<String>>> Controller<TabsState<String>, W> for Close {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut TabsState<String>,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event
+            && let Some(index) = cmd.get(CLOSE)
+        {
+            data.close(*index);
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+fn names(state: &TabsState<String>) -> Vec<&str> {
+    state.tabs.iter().map(String::as_str).collect()
+}
+
+#[test]
+fn click_selects_tab() {
+    mount(state(&["A", "B", "C"]), tabs(), |h| {
+        h.click((TAB * 1.5, BAR));
+        assert_eq!(h.data().selected, 1);
+    });
+}
+
+#[test]
+fn ctrl_tab_cycles_through_tabs() {
+    mount(state(&["A", "B", "C"]), tabs(), |h| {
+        h.click((TAB * 2.5, BAR));
+        h.key_down_with(Modifiers::CONTROL, KbKey::Tab);
+        assert_eq!(h.data().selected, 0);
+
+        h.key_down_with(Modifiers::CONTROL | Modifiers::SHIFT, KbKey::Tab);
+        assert_eq!(h.data().selected, 2);
+    });
+}
+
+#[test]
+fn close_button_keeps_selected_tab() {
+    mount(state(&["A", "B", "C"]), tabs().closable(), |h| {
+        h.click((TAB * 2.5, BAR));
+        // the cross sits at the right end of the first tab
+        h.click((TAB - 14., BAR));
+        assert_eq!(names(h.data()), ["B", "C"]);
+        assert_eq!(h.data().selected_tab().map(String::as_str), Some("C"));
+    });
+}
+
+#[test]
+fn dragging_reorders_tabs() {
+    mount(state(&["A", "B", "C"]), tabs(), |h| {
+        h.mouse_move((TAB * 0.5, BAR));
+        h.mouse_down((TAB * 0.5, BAR));
+        h.mouse_move((TAB * 0.5 + 10., BAR));
+        h.mouse_move((TAB * 1.5 + 10., BAR));
+        h.mouse_up((TAB * 1.5 + 10., BAR));
+
+        assert_eq!(names(h.data()), ["B", "A", "C"]);
+        assert_eq!(h.data().selected, 1);
+    });
+}
+
+#[test]
+fn closing_tabs_elsewhere_stops_the_drag() {
+    mount(state(&["A", "B", "C"]), tabs().controller(Close), |h| {
+        h.mouse_move((TAB * 1.5, BAR));
+        h.mouse_down((TAB * 1.5, BAR));
+        h.mouse_move((TAB * 1.5 + 10., BAR));
+        h.submit_command(CLOSE.with(0));
+        assert_eq!(names(h.data()), ["B", "C"]);
+
+        // the drag held "B", which is no longer the second tab
+        h.mouse_move((TAB * 0.5, BAR));
+        h.mouse_up((TAB * 0.5, BAR));
+        assert_eq!(names(h.data()), ["B", "C"]);
+    });
+}
+
+#[test]
+fn overflowing_tabs_list_in_dropdown() {
+    let many = ["A", "B", "C", "D", "E", "F", "G", "H"];
+    mount(state(&many), tabs(), |h| {
+        // eight tabs don't fit the 400 pixel window, the button sits at its right end
+        h.click((395., BAR));
+        assert_eq!(h.sub_windows_requested(), 1);
+    });
+}
+
+#[test]
+fn wheel_scrolls_hidden_tabs_into_view() {
+    let many = ["A", "B", "C", "D", "E", "F", "G", "H"];
+    mount(state(&many), tabs(), |h| {
+        h.wheel((100., BAR), Vec2::new(0., TAB));
+        h.layout();
+        h.click((5., BAR));
+        assert_eq!(h.data().selected, 1);
+    });
+}
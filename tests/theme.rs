@@ -0,0 +1,69 @@
+mod harness;
+
+use std::time::Duration;
+
+use druid::widget::Controller;
+use druid::widget::prelude::*;
+use druid::{Color, Selector, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use druid_gio_widgets::theme::{Preset, ThemeScope};
+use harness::render;
+
+const SIZE: Size = Size::new(120., 40.);
+const DARK_FILL: Color = Color::rgb8(43, 45, 48);
+const SWITCH: Selector<Preset> = Selector::new("tests.theme.switch");
+const FRAME: u64 = 100_000_000;
+
+// Stands in for whatever part of the app flips the theme.
+struct Switch;
+
+impl<W: Widget<Preset>> Controller<Preset, W> for Switch {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Preset,
+        env: &Env,
+    ) {
+        if let Event::Command(cmd) = event
+            && let Some(preset) = cmd.get(SWITCH)
+        {
+            *data = *preset;
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+fn themed_button() -> ThemeScope<Preset, impl Widget<Preset>> {
+    let button = AdvancedButton::new("OK").controller(Switch);
+    ThemeScope::new(|preset: &Preset| *preset, button)
+}
+
+#[test]
+fn switching_preset_repaints() {
+    let image = render(Preset::Dark, themed_button(), SIZE, |h| {
+        h.submit_command(SWITCH.with(Preset::Light));
+    });
+    assert_eq!(image.pixel(1, 20), Color::WHITE);
+}
+
+#[test]
+fn transition_fades_between_presets() {
+    let button = themed_button().with_transition(Duration::from_nanos(2 * FRAME));
+    let halfway = render(Preset::Dark, button, SIZE, |h| {
+        h.submit_command(SWITCH.with(Preset::Light));
+        h.event(Event::AnimFrame(FRAME));
+    });
+    let pixel = halfway.pixel(1, 20);
+    assert_ne!(pixel, DARK_FILL);
+    assert_ne!(pixel, Color::WHITE);
+
+    let button = themed_button().with_transition(Duration::from_nanos(2 * FRAME));
+    let done = render(Preset::Dark, button, SIZE, |h| {
+        h.submit_command(SWITCH.with(Preset::Light));
+        h.event(Event::AnimFrame(FRAME));
+        h.event(Event::AnimFrame(FRAME));
+    });
+    assert_eq!(done.pixel(1, 20), Color::WHITE);
+}
@@ -0,0 +1,67 @@
+mod harness;
+
+use druid::widget::SizedBox;
+use druid::{Color, Size};
+use druid_gio_widgets::prelude::*;
+use harness::{DEFAULT_SIZE, mount, render};
+
+const SIZE: Size = Size::new(400., 300.);
+const MARGIN: f64 = 16.;
+// the dark preset's error color, the fallback without a preset
+const ERROR: Color = Color::rgb8(0xf7, 0x54, 0x64);
+
+selectors! {
+    UNDO,
+}
+
+fn host() -> ToastHost<()> {
+    ToastHost::new(SizedBox::empty().expand())
+}
+
+#[test]
+fn toast_slides_into_corner() {
+    let image = render((), host(), SIZE, |h| {
+        h.submit_command(SHOW_TOAST.with(Toast::error("Could not save")));
+        h.settle();
+        h.layout();
+    });
+    // the border of the toast, well clear of its rounded corners
+    let x = (SIZE.width - MARGIN) as usize - 1;
+    let y = (SIZE.height - MARGIN) as usize - 10;
+    assert_eq!(image.pixel(x, y), ERROR);
+}
+
+#[test]
+fn toast_is_hidden_before_it_slides_in() {
+    let image = render((), host(), SIZE, |h| {
+        h.submit_command(SHOW_TOAST.with(Toast::error("Could not save")));
+        h.layout();
+    });
+    let x = (SIZE.width - MARGIN) as usize - 1;
+    let y = (SIZE.height - MARGIN) as usize - 10;
+    assert_ne!(image.pixel(x, y), ERROR);
+}
+
+#[test]
+fn action_click_reports_command() {
+    mount((), host(), |h| {
+        let toast = Toast::info("Deleted").with_action("Undo deleting", UNDO);
+        h.submit_command(SHOW_TOAST.with(toast));
+        h.settle();
+        h.layout();
+
+        // the action button sits left of the close button, at the right end of the toast
+        let pos = (
+            DEFAULT_SIZE.width - MARGIN - 50.,
+            DEFAULT_SIZE.height - MARGIN - 18.,
+        );
+        h.click(pos);
+        assert_eq!(h.commands_of(UNDO).len(), 1);
+
+        // the toast is gone once it slid out, so the same click reaches nothing
+        h.settle();
+        h.layout();
+        h.click(pos);
+        assert_eq!(h.commands_of(UNDO).len(), 1);
+    });
+}
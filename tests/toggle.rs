@@ -0,0 +1,43 @@
+mod harness;
+
+use druid::{Color, KbKey, Size, WidgetExt};
+use druid_gio_widgets::prelude::*;
+use harness::{mount, render};
+
+const TRACK: Size = Size::new(36., 20.);
+const FILL: Color = Color::rgb8(43, 45, 48);
+const ACCENT: Color = Color::rgb8(52, 116, 240);
+
+#[test]
+fn click_and_keys_flip() {
+    mount(false, Toggle::new().with_label("Wi-Fi"), |h| {
+        h.click((10., 10.));
+        assert!(*h.data());
+
+        // the click gave it focus
+        h.key_down(KbKey::Character(" ".into()));
+        assert!(!*h.data());
+        h.key_down(KbKey::Enter);
+        assert!(*h.data());
+    });
+}
+
+#[test]
+fn disabled_ignores_clicks() {
+    mount(false, Toggle::new().disabled_if(|_, _| true), |h| {
+        h.click((10., 10.));
+        assert!(!*h.data());
+    });
+}
+
+#[test]
+fn track_fills_with_accent_when_on() {
+    let off = render(false, Toggle::new(), TRACK, |_| {});
+    assert_eq!(off.pixel(30, 10), FILL);
+
+    let on = render(false, Toggle::new(), TRACK, |h| {
+        h.click((10., 10.));
+        h.settle();
+    });
+    assert_eq!(on.pixel(5, 10), ACCENT);
+}
@@ -0,0 +1,101 @@
+mod harness;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use druid::im::vector;
+use druid::widget::Label;
+use druid::{Env, KbKey};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+const ROW: f64 = 24.;
+// past the chevron of a root node
+const LABEL: f64 = 50.;
+
+fn row(index: usize) -> f64 {
+    (index as f64 + 0.5) * ROW
+}
+
+fn tree() -> Tree<String> {
+    Tree::new(|| Label::new(|name: &String, _: &Env| name.clone()))
+}
+
+fn files() -> TreeState<String> {
+    TreeState::new(vector![
+        TreeNode::branch(
+            "src".to_owned(),
+            [
+                TreeNode::leaf("lib.rs".to_owned()),
+                TreeNode::leaf("main.rs".to_owned()),
+            ],
+        ),
+        TreeNode::leaf("Cargo.toml".to_owned()),
+    ])
+}
+
+#[test]
+fn chevron_click_expands_node() {
+    mount(files(), tree(), |h| {
+        h.click((9., row(0)));
+        assert!(h.data().roots[0].expanded);
+        assert_eq!(h.data().selected, None);
+
+        h.click((LABEL, row(2)));
+        assert_eq!(h.data().selected_node().unwrap().item, "main.rs");
+    });
+}
+
+#[test]
+fn arrow_keys_walk_the_tree() {
+    mount(files(), tree(), |h| {
+        h.click((LABEL, row(0)));
+        h.key_down(KbKey::ArrowRight);
+        assert!(h.data().roots[0].expanded);
+
+        h.key_down(KbKey::ArrowRight);
+        assert_eq!(h.data().selected, Some(vector![0, 0]));
+        h.key_down(KbKey::ArrowDown);
+        assert_eq!(h.data().selected, Some(vector![0, 1]));
+
+        h.key_down(KbKey::ArrowLeft);
+        assert_eq!(h.data().selected, Some(vector![0]));
+        h.key_down(KbKey::ArrowLeft);
+        assert!(!h.data().roots[0].expanded);
+        h.key_down(KbKey::ArrowDown);
+        assert_eq!(h.data().selected, Some(vector![1]));
+    });
+}
+
+#[test]
+fn collapsing_selects_collapsed_node() {
+    mount(files(), tree(), |h| {
+        h.click((9., row(0)));
+        h.click((LABEL, row(1)));
+        h.click((9., row(0)));
+        assert_eq!(h.data().selected, Some(vector![0]));
+    });
+}
+
+#[test]
+fn children_load_when_first_expanded() {
+    let pending: Rc<RefCell<Vec<ChildLoader>>> = Rc::default();
+    let loader = pending.clone();
+    let tree = tree().with_loader(move |_, child_loader| loader.borrow_mut().push(child_loader));
+    let state = TreeState::new(vector![TreeNode::unloaded("assets".to_owned())]);
+    mount(state, tree, |h| {
+        h.click((9., row(0)));
+        assert_eq!(pending.borrow().len(), 1);
+        assert_eq!(pending.borrow()[0].path(), &vector![0]);
+
+        // collapsing and expanding again while loading doesn't ask twice
+        h.click((9., row(0)));
+        h.click((9., row(0)));
+        assert_eq!(pending.borrow().len(), 1);
+
+        let child_loader = pending.borrow_mut().pop().unwrap();
+        h.submit_command(child_loader.into_command(vector![TreeNode::leaf("logo.png".to_owned())]));
+        h.click((LABEL, row(1)));
+        assert_eq!(h.data().selected_node().unwrap().item, "logo.png");
+    });
+}
@@ -0,0 +1,109 @@
+mod harness;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use druid::im::Vector;
+use druid::widget::Label;
+use druid::widget::prelude::*;
+use druid::{KbKey, Vec2};
+use druid_gio_widgets::prelude::*;
+use harness::mount;
+
+const ROW: f64 = 20.;
+
+fn state(len: u32) -> VirtualListState<u32> {
+    VirtualListState::new((0..len).collect())
+}
+
+fn list(built: &Rc<Cell<usize>>) -> VirtualList<u32> {
+    let built = built.clone();
+    VirtualList::new(
+        move || {
+            built.set(built.get() + 1);
+            Label::new(|item: &u32, _: &Env| item.to_string())
+        },
+        ROW,
+    )
+}
+
+/// A row as tall as its item.
+struct Tall;
+
+impl Widget<u32> for Tall {
+    fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut u32, _: &Env) {}
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &u32, _: &Env) {}
+    fn update(&mut self, _: &mut UpdateCtx, _: &u32, _: &u32, _: &Env) {}
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, data: &u32, _: &Env) -> Size {
+        bc.constrain(Size::new(bc.max().width, *data as f64))
+    }
+    fn paint(&mut self, _: &mut PaintCtx, _: &u32, _: &Env) {}
+}
+
+#[test]
+fn builds_only_visible_rows() {
+    let built = Rc::new(Cell::new(0));
+    mount(state(100_000), list(&built), |h| {
+        h.relayout();
+        // the 400 pixel window shows 20 rows, plus a few on each side
+        assert!(built.get() < 40, "built {} rows", built.get());
+
+        built.set(0);
+        h.wheel((5., 5.), Vec2::new(0., 10. * ROW));
+        h.relayout();
+        assert!(built.get() <= 10, "built {} rows", built.get());
+    });
+}
+
+#[test]
+fn click_selects_row() {
+    mount(state(100_000), list(&Rc::default()), |h| {
+        h.click((5., ROW * 2.5));
+        assert_eq!(h.data().selected, Some(2));
+        assert_eq!(h.data().selected_item(), Some(&2));
+    });
+}
+
+#[test]
+fn scroll_to_index_reveals_row() {
+    mount(state(100_000), list(&Rc::default()), |h| {
+        h.submit_command(SCROLL_TO_INDEX.with(50_000).to(h.widget_id()));
+        h.relayout();
+        // rows below the view scroll up to its bottom edge
+        h.click((5., 400. - ROW / 2.));
+        assert_eq!(h.data().selected, Some(50_000));
+    });
+}
+
+#[test]
+fn arrow_keys_move_selection() {
+    mount(state(100_000), list(&Rc::default()), |h| {
+        h.click((5., 5.));
+        h.key_down(KbKey::ArrowDown);
+        h.key_down(KbKey::ArrowDown);
+        assert_eq!(h.data().selected, Some(2));
+
+        h.key_down(KbKey::End);
+        assert_eq!(h.data().selected, Some(99_999));
+        h.relayout();
+        h.click((5., 400. - ROW / 2.));
+        assert_eq!(h.data().selected, Some(99_999));
+    });
+}
+
+#[test]
+fn measured_rows_keep_their_height() {
+    let items: Vector<u32> = (0..1000)
+        .map(|index| if index % 2 == 0 { 10 } else { 30 })
+        .collect();
+    let list =
+        VirtualList::new(|| Tall, ROW).with_row_height(RowHeight::Measured { estimate: ROW });
+    mount(VirtualListState::new(items), list, |h| {
+        h.relayout();
+        // rows start at 0, 10, 40, 50, 80
+        h.click((5., 45.));
+        assert_eq!(h.data().selected, Some(2));
+        h.click((5., 60.));
+        assert_eq!(h.data().selected, Some(3));
+    });
+}